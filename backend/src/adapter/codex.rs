@@ -0,0 +1,173 @@
+use serde_json::{Map, Value as JsonValue};
+use toml_edit::{Array, DocumentMut, InlineTable, Item, Table, TableLike, Value};
+
+use super::{AdapterCapabilities, AgentAdapter, AgentKind, McpServersAdapter};
+use crate::config::McpSettings;
+use crate::error::{BackendError, BackendResult};
+
+const SERVERS_KEY: &str = "mcp_servers";
+
+static ENTRIES: McpServersAdapter =
+    McpServersAdapter::new(AgentKind::Codex, "url", "url").with_headers_path(&["http_headers"]);
+
+/// Codex CLI `config.toml` 의 `[mcp_servers.<id>]` 테이블을 다룹니다.
+/// 쓸 때는 `toml_edit` 으로 바뀐 키만 고쳐 주석과 다른 섹션의 서식을 그대로 둡니다.
+pub struct CodexAdapter;
+
+impl AgentAdapter for CodexAdapter {
+    fn kind(&self) -> AgentKind {
+        AgentKind::Codex
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities {
+            sse: false,
+            enabled_flag: false,
+            ..AdapterCapabilities::FULL
+        }
+    }
+
+    fn detect(&self, content: &str) -> bool {
+        parse_root(content)
+            .map(|root| root.get(SERVERS_KEY).is_some_and(JsonValue::is_object))
+            .unwrap_or(false)
+    }
+
+    fn parse(&self, content: &str) -> BackendResult<McpSettings> {
+        let root = parse_root(content)?;
+        Ok(McpSettings {
+            servers: ENTRIES.parse_entries(root.get(SERVERS_KEY), SERVERS_KEY)?,
+            ..Default::default()
+        })
+    }
+
+    fn render(&self, settings: &McpSettings, existing: Option<&str>) -> BackendResult<String> {
+        let content = existing.unwrap_or_default();
+        let root = parse_root(content)?;
+        let previous = root.get(SERVERS_KEY);
+        let entries = ENTRIES.render_entries(settings, previous);
+
+        let mut document: DocumentMut = content
+            .parse()
+            .map_err(|err| BackendError::InvalidConfig(format!("{err}")))?;
+        let servers = document
+            .entry(SERVERS_KEY)
+            .or_insert_with(|| {
+                // `[mcp_servers]` 헤더 없이 `[mcp_servers.<id>]` 만 쓰도록 합니다.
+                let mut table = Table::new();
+                table.set_implicit(true);
+                Item::Table(table)
+            })
+            .as_table_mut()
+            .ok_or_else(|| {
+                BackendError::InvalidConfig(format!("'{SERVERS_KEY}' must be a table"))
+            })?;
+
+        let stale: Vec<String> = servers
+            .iter()
+            .map(|(id, _)| id.to_string())
+            .filter(|id| !entries.contains_key(id))
+            .collect();
+        for id in stale {
+            servers.remove(&id);
+        }
+        for (id, entry) in &entries {
+            let JsonValue::Object(fields) = entry else {
+                continue;
+            };
+            let old = previous
+                .and_then(|servers| servers.get(id))
+                .and_then(JsonValue::as_object);
+            match servers.get_mut(id).and_then(Item::as_table_like_mut) {
+                Some(table) => update_table(table, old, fields),
+                None => {
+                    let mut table = Table::new();
+                    update_table(&mut table, None, fields);
+                    servers.insert(id, Item::Table(table));
+                }
+            }
+        }
+        Ok(document.to_string())
+    }
+}
+
+fn parse_root(content: &str) -> BackendResult<Map<String, JsonValue>> {
+    let table: toml::Table =
+        toml::from_str(content).map_err(|err| BackendError::InvalidConfig(err.to_string()))?;
+    match serde_json::to_value(table)? {
+        JsonValue::Object(root) => Ok(root),
+        _ => Err(BackendError::InvalidConfig(
+            "top-level value must be a table".to_string(),
+        )),
+    }
+}
+
+/// 이전 값과 달라진 키만 다시 써서, 그대로인 키의 서식과 주석을 유지합니다.
+fn update_table(
+    table: &mut dyn TableLike,
+    old: Option<&Map<String, JsonValue>>,
+    fields: &Map<String, JsonValue>,
+) {
+    let stale: Vec<String> = table
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !fields.contains_key(key))
+        .collect();
+    for key in stale {
+        table.remove(&key);
+    }
+
+    for (key, value) in fields {
+        let old_value = old.and_then(|old| old.get(key));
+        if old_value == Some(value) {
+            continue;
+        }
+        let existing = table.get_mut(key);
+        if let (Some(item), JsonValue::Object(nested)) = (existing, value) {
+            if let Some(nested_table) = item.as_table_like_mut() {
+                update_table(
+                    nested_table,
+                    old_value.and_then(JsonValue::as_object),
+                    nested,
+                );
+                continue;
+            }
+        }
+        match to_toml(value) {
+            Some(mut new_value) => {
+                if let Some(previous) = table.get(key).and_then(Item::as_value) {
+                    *new_value.decor_mut() = previous.decor().clone();
+                }
+                table.insert(key, Item::Value(new_value));
+            }
+            None => {
+                table.remove(key);
+            }
+        }
+    }
+}
+
+fn to_toml(value: &JsonValue) -> Option<Value> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::Bool(flag) => Some(Value::from(*flag)),
+        JsonValue::Number(number) => match number.as_i64() {
+            Some(integer) => Some(Value::from(integer)),
+            None => number.as_f64().map(Value::from),
+        },
+        JsonValue::String(text) => Some(Value::from(text.as_str())),
+        JsonValue::Array(items) => {
+            let array: Array = items.iter().filter_map(to_toml).collect();
+            Some(Value::Array(array))
+        }
+        JsonValue::Object(fields) => {
+            let mut table = InlineTable::new();
+            for (key, value) in fields {
+                if let Some(value) = to_toml(value) {
+                    table.insert(key, value);
+                }
+            }
+            Some(Value::InlineTable(table))
+        }
+    }
+}
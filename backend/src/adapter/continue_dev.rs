@@ -0,0 +1,276 @@
+use serde_json::{Map, Value};
+
+use super::{AdapterCapabilities, AgentAdapter, AgentKind, McpServersAdapter};
+use crate::config::McpSettings;
+use crate::error::{BackendError, BackendResult};
+
+const SERVERS_KEY: &str = "mcpServers";
+
+static ENTRIES: McpServersAdapter = McpServersAdapter::new(AgentKind::Continue, "url", "url")
+    .with_headers_path(&["requestOptions", "headers"]);
+
+/// Continue `config.yaml` 의 `mcpServers` 목록을 다룹니다. 항목의 `name` 을 서버 ID 로 씁니다.
+/// 쓸 때는 최상위 `mcpServers` 블록의 텍스트만 교체해 models, rules 등 다른 섹션은 그대로 둡니다.
+pub struct ContinueAdapter;
+
+impl ContinueAdapter {
+    /// 목록을 `name` 을 키로 하는 맵으로 바꿔 `mcpServers` 형식의 변환 로직을 재사용합니다.
+    fn entries_by_name(root: &Map<String, Value>) -> BackendResult<Option<Value>> {
+        let items = match root.get(SERVERS_KEY) {
+            Some(Value::Array(items)) => items,
+            Some(Value::Null) | None => return Ok(None),
+            Some(_) => {
+                return Err(BackendError::InvalidConfig(format!(
+                    "'{SERVERS_KEY}' must be a list"
+                )))
+            }
+        };
+        let mut entries = Map::new();
+        for item in items {
+            let mut entry = item.as_object().cloned().ok_or_else(|| {
+                BackendError::InvalidConfig(format!("'{SERVERS_KEY}' items must be mappings"))
+            })?;
+            let name = match entry.shift_remove("name") {
+                Some(Value::String(name)) => name,
+                _ => {
+                    return Err(BackendError::InvalidConfig(format!(
+                        "'{SERVERS_KEY}' items need a 'name'"
+                    )))
+                }
+            };
+            entries.insert(name, Value::Object(entry));
+        }
+        Ok(Some(Value::Object(entries)))
+    }
+}
+
+impl AgentAdapter for ContinueAdapter {
+    fn kind(&self) -> AgentKind {
+        AgentKind::Continue
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities {
+            enabled_flag: false,
+            ..AdapterCapabilities::FULL
+        }
+    }
+
+    fn detect(&self, content: &str) -> bool {
+        parse_root(content)
+            .map(|root| root.get(SERVERS_KEY).is_some_and(Value::is_array))
+            .unwrap_or(false)
+    }
+
+    fn parse(&self, content: &str) -> BackendResult<McpSettings> {
+        let root = parse_root(content)?;
+        let entries = Self::entries_by_name(&root)?;
+        Ok(McpSettings {
+            servers: ENTRIES.parse_entries(entries.as_ref(), SERVERS_KEY)?,
+            ..Default::default()
+        })
+    }
+
+    fn render(&self, settings: &McpSettings, existing: Option<&str>) -> BackendResult<String> {
+        let content = existing.unwrap_or_default();
+        let root = parse_root(content)?;
+        let previous = Self::entries_by_name(&root)?;
+        let items: Vec<Value> = ENTRIES
+            .render_entries(settings, previous.as_ref())
+            .into_iter()
+            .map(|(name, entry)| {
+                let mut item = Map::new();
+                item.insert("name".to_string(), Value::from(name));
+                if let Value::Object(fields) = entry {
+                    // 원격 서버는 `type` 이 있어야 하며, Continue 는 `http` 대신 `streamable-http` 를 씁니다.
+                    if fields.contains_key("url") && !fields.contains_key("type") {
+                        item.insert("type".to_string(), Value::from("streamable-http"));
+                    }
+                    for (key, value) in fields {
+                        let value = match (key.as_str(), value) {
+                            ("type", Value::String(kind)) if kind == "http" => {
+                                Value::from("streamable-http")
+                            }
+                            (_, value) => value,
+                        };
+                        item.insert(key, value);
+                    }
+                }
+                Value::Object(item)
+            })
+            .collect();
+        replace_block(content, &items)
+    }
+}
+
+fn parse_root(content: &str) -> BackendResult<Map<String, Value>> {
+    if content.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    let value: Value = serde_yaml::from_str(content)
+        .map_err(|err| BackendError::InvalidConfig(err.to_string()))?;
+    match value {
+        Value::Object(root) => Ok(root),
+        Value::Null => Ok(Map::new()),
+        _ => Err(BackendError::InvalidConfig(
+            "top-level value must be a mapping".to_string(),
+        )),
+    }
+}
+
+/// 최상위 `mcpServers:` 줄부터 들여쓴 줄(또는 0 열의 목록 항목)이 이어지는 곳까지를 새 목록으로 바꿉니다.
+/// 블록 뒤쪽의 빈 줄과 주석은 다음 섹션의 것으로 보고 남겨 둡니다. 블록이 없으면 파일 끝에 추가합니다.
+/// 내용이 같은 항목은 원문을 그대로 두고, 바뀐 항목만 다시 써서 그 항목 안의 주석만 사라집니다.
+fn replace_block(content: &str, items: &[Value]) -> BackendResult<String> {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let header = lines.iter().position(|line| {
+        line.strip_prefix(SERVERS_KEY)
+            .is_some_and(|rest| rest.trim_start().starts_with(':'))
+    });
+
+    let Some(header) = header else {
+        let mut updated = content.to_string();
+        if !updated.is_empty() && !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated.push_str(&render_header(items, None));
+        updated.push_str(&render_items(items, "  ")?);
+        return Ok(updated);
+    };
+
+    let mut end = header + 1;
+    let mut scan = header + 1;
+    while let Some(line) = lines.get(scan) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || line.starts_with('#') {
+            scan += 1;
+            continue;
+        }
+        if line.starts_with([' ', '\t']) || trimmed == "-" || line.starts_with("- ") {
+            scan += 1;
+            end = scan;
+            continue;
+        }
+        break;
+    }
+
+    let indent = lines[header + 1..end]
+        .iter()
+        .find(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .unwrap_or("  ");
+    let (lead, previous) = split_items(&lines[header + 1..end], indent);
+
+    let mut updated: String = lines[..header].concat();
+    updated.push_str(&render_header(items, Some(lines[header])));
+    if !items.is_empty() {
+        updated.push_str(&lead);
+    }
+    for item in items {
+        let old = previous
+            .iter()
+            .find(|old| old.value.as_ref().and_then(|value| value.get("name")) == item.get("name"));
+        match old {
+            Some(old) if old.value.as_ref() == Some(item) => {
+                updated.push_str(&old.text);
+                if !updated.ends_with('\n') {
+                    updated.push('\n');
+                }
+            }
+            Some(old) => {
+                updated.push_str(&old.comments);
+                updated.push_str(&render_items(std::slice::from_ref(item), indent)?);
+            }
+            None => updated.push_str(&render_items(std::slice::from_ref(item), indent)?),
+        }
+    }
+    updated.push_str(&lines[end..].concat());
+    Ok(updated)
+}
+
+/// 기존 블록의 목록 항목 하나. `comments` 는 항목 바로 앞의 주석과 빈 줄이며 `text` 에도 포함됩니다.
+struct BlockItem {
+    comments: String,
+    text: String,
+    value: Option<Value>,
+}
+
+/// 블록 본문을 첫 항목 앞의 줄과 항목별 원문으로 나눕니다.
+fn split_items(lines: &[&str], indent: &str) -> (String, Vec<BlockItem>) {
+    let is_item = |line: &str| {
+        line.strip_prefix(indent)
+            .is_some_and(|rest| rest.trim_end() == "-" || rest.starts_with("- "))
+    };
+    let is_filler = |line: &str| line.trim().is_empty() || line.trim_start().starts_with('#');
+    let starts: Vec<usize> = (0..lines.len()).filter(|&i| is_item(lines[i])).collect();
+    // 항목 앞의 주석은 앞 항목이 아니라 뒤 항목에 붙입니다.
+    let mut bounds: Vec<(usize, usize)> = Vec::new();
+    for (index, &start) in starts.iter().enumerate() {
+        let floor = if index == 0 { 0 } else { starts[index - 1] + 1 };
+        let mut begin = start;
+        while begin > floor && is_filler(lines[begin - 1]) {
+            begin -= 1;
+        }
+        bounds.push((begin, start));
+    }
+    let lead = match bounds.first() {
+        Some(&(begin, _)) => lines[..begin].concat(),
+        None => lines.concat(),
+    };
+    let items = bounds
+        .iter()
+        .enumerate()
+        .map(|(index, &(begin, start))| {
+            let end = bounds.get(index + 1).map_or(lines.len(), |next| next.0);
+            let body = lines[start..end].concat();
+            let value = serde_yaml::from_str::<Vec<Value>>(&body)
+                .ok()
+                .and_then(|values| values.into_iter().next());
+            BlockItem {
+                comments: lines[begin..start].concat(),
+                text: lines[begin..end].concat(),
+                value,
+            }
+        })
+        .collect();
+    (lead, items)
+}
+
+/// 항목이 있으면 기존 머리 줄(끝 주석 포함)을 유지하고, 없으면 빈 목록으로 씁니다.
+fn render_header(items: &[Value], existing: Option<&str>) -> String {
+    if items.is_empty() {
+        return format!("{SERVERS_KEY}: []\n");
+    }
+    match existing {
+        Some(line)
+            if line[SERVERS_KEY.len()..]
+                .trim_start()
+                .strip_prefix(':')
+                .is_some_and(|rest| {
+                    rest.trim().is_empty() || rest.trim_start().starts_with('#')
+                }) =>
+        {
+            let mut line = line.to_string();
+            if !line.ends_with('\n') {
+                line.push('\n');
+            }
+            line
+        }
+        _ => format!("{SERVERS_KEY}:\n"),
+    }
+}
+
+fn render_items(items: &[Value], indent: &str) -> BackendResult<String> {
+    if items.is_empty() {
+        return Ok(String::new());
+    }
+    let rendered =
+        serde_yaml::to_string(items).map_err(|err| BackendError::Other(err.to_string()))?;
+    let mut block = String::new();
+    for line in rendered.lines() {
+        block.push_str(indent);
+        block.push_str(line);
+        block.push('\n');
+    }
+    Ok(block)
+}
@@ -0,0 +1,284 @@
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_json::{Map, Value};
+
+use crate::error::{BackendError, BackendResult};
+
+const DEFAULT_INDENT: &str = "    ";
+
+/// 주석(`//`, `/* */`)과 끝 쉼표를 허용하는 JSON 을 읽습니다.
+pub fn parse_object(content: &str) -> BackendResult<Map<String, Value>> {
+    match serde_json::from_str::<Value>(&strip(content))? {
+        Value::Object(root) => Ok(root),
+        _ => Err(BackendError::InvalidConfig(
+            "top-level value must be an object".to_string(),
+        )),
+    }
+}
+
+/// 내용이 비어 있을 때 새로 만들 문서를 들여쓰기 규칙에 맞춰 직렬화합니다.
+pub fn to_document(value: &Value) -> BackendResult<String> {
+    let mut rendered = to_pretty(value, DEFAULT_INDENT, "")?;
+    rendered.push('\n');
+    Ok(rendered)
+}
+
+/// `path` 위치의 값만 `value` 로 바꾸고 나머지 텍스트(주석, 서식 포함)는 그대로 둡니다.
+/// 경로 중간의 키가 없으면 남은 경로를 감싼 객체를 가장 가까운 상위 객체 끝에 추가합니다.
+pub fn replace_value(content: &str, path: &[&str], value: &Value) -> BackendResult<String> {
+    let stripped = strip(content);
+    let unit = detect_indent(content);
+    let invalid = || BackendError::InvalidConfig("malformed JSON document".to_string());
+
+    let mut object_start = stripped
+        .find(|c: char| !c.is_whitespace())
+        .filter(|&start| stripped.as_bytes()[start] == b'{')
+        .ok_or_else(|| {
+            BackendError::InvalidConfig("top-level value must be an object".to_string())
+        })?;
+
+    for (depth, key) in path.iter().enumerate() {
+        let (members, close) = object_members(&stripped, object_start).ok_or_else(invalid)?;
+        match members.iter().find(|member| member.key == *key) {
+            Some(member) if depth + 1 == path.len() => {
+                let indent = line_indent(content, member.key_start);
+                let rendered = to_pretty(value, &unit, indent)?;
+                return Ok(splice(
+                    content,
+                    member.value_start,
+                    member.value_end,
+                    &rendered,
+                ));
+            }
+            Some(member) if stripped.as_bytes()[member.value_start] == b'{' => {
+                object_start = member.value_start;
+            }
+            Some(_) => {
+                return Err(BackendError::InvalidConfig(format!(
+                    "'{}' must be an object",
+                    path[..=depth].join(".")
+                )))
+            }
+            None => {
+                let nested = path[depth + 1..]
+                    .iter()
+                    .rev()
+                    .fold(value.clone(), |inner, key| {
+                        Value::Object(Map::from_iter([(key.to_string(), inner)]))
+                    });
+                let parent_indent = line_indent(content, object_start);
+                let member_indent = format!("{parent_indent}{unit}");
+                let member = format!(
+                    "{}: {}",
+                    Value::from(*key),
+                    to_pretty(&nested, &unit, &member_indent)?
+                );
+                let updated = match members.last() {
+                    Some(last) => splice(
+                        content,
+                        last.value_end,
+                        last.value_end,
+                        &format!(",\n{member_indent}{member}"),
+                    ),
+                    None if stripped[object_start + 1..close].trim().is_empty() => splice(
+                        content,
+                        object_start + 1,
+                        close,
+                        &format!("\n{member_indent}{member}\n{parent_indent}"),
+                    ),
+                    None => splice(
+                        content,
+                        object_start + 1,
+                        object_start + 1,
+                        &format!("\n{member_indent}{member}"),
+                    ),
+                };
+                return Ok(updated);
+            }
+        }
+    }
+    Err(BackendError::Other("empty JSON path".to_string()))
+}
+
+/// 주석과 끝 쉼표를 같은 길이의 공백으로 바꿔, 바이트 위치가 원문과 일치하는 JSON 을 만듭니다.
+fn strip(content: &str) -> String {
+    let bytes = content.as_bytes();
+    let mut out = bytes.to_vec();
+    let mut last_comma = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i = string_end(bytes, i);
+                last_comma = None;
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    out[i] = b' ';
+                    i += 1;
+                }
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = content[i + 2..]
+                    .find("*/")
+                    .map(|offset| i + 2 + offset + 2)
+                    .unwrap_or(bytes.len());
+                for byte in &mut out[i..end] {
+                    if *byte != b'\n' {
+                        *byte = b' ';
+                    }
+                }
+                i = end;
+                continue;
+            }
+            b',' => last_comma = Some(i),
+            b'}' | b']' => {
+                if let Some(comma) = last_comma.take() {
+                    out[comma] = b' ';
+                }
+            }
+            byte if byte.is_ascii_whitespace() => {}
+            _ => last_comma = None,
+        }
+        i += 1;
+    }
+    // 주석은 ASCII 경계에서 시작하고 끝나므로 멀티바이트 문자가 잘리지 않습니다.
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+struct Member {
+    key: String,
+    key_start: usize,
+    value_start: usize,
+    value_end: usize,
+}
+
+/// `start` 의 `{` 부터 객체 멤버의 위치와 닫는 `}` 위치를 구합니다. `text` 는 `strip` 결과여야 합니다.
+fn object_members(text: &str, start: usize) -> Option<(Vec<Member>, usize)> {
+    let bytes = text.as_bytes();
+    let mut members = Vec::new();
+    let mut pos = skip_whitespace(bytes, start + 1);
+    if bytes.get(pos) == Some(&b'}') {
+        return Some((members, pos));
+    }
+    loop {
+        if bytes.get(pos) != Some(&b'"') {
+            return None;
+        }
+        let key_start = pos;
+        let key_end = string_end(bytes, pos);
+        let key: String = serde_json::from_str(text.get(key_start..key_end)?).ok()?;
+        pos = skip_whitespace(bytes, key_end);
+        if bytes.get(pos) != Some(&b':') {
+            return None;
+        }
+        let value_start = skip_whitespace(bytes, pos + 1);
+        let value_end = value_end(text, value_start)?;
+        members.push(Member {
+            key,
+            key_start,
+            value_start,
+            value_end,
+        });
+        pos = skip_whitespace(bytes, value_end);
+        match bytes.get(pos) {
+            Some(b',') => pos = skip_whitespace(bytes, pos + 1),
+            Some(b'}') => return Some((members, pos)),
+            _ => return None,
+        }
+    }
+}
+
+fn value_end(text: &str, start: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    match bytes.get(start)? {
+        b'{' => object_members(text, start).map(|(_, close)| close + 1),
+        b'[' => {
+            let mut pos = skip_whitespace(bytes, start + 1);
+            if bytes.get(pos) == Some(&b']') {
+                return Some(pos + 1);
+            }
+            loop {
+                pos = skip_whitespace(bytes, value_end(text, pos)?);
+                match bytes.get(pos) {
+                    Some(b',') => pos = skip_whitespace(bytes, pos + 1),
+                    Some(b']') => return Some(pos + 1),
+                    _ => return None,
+                }
+            }
+        }
+        b'"' => Some(string_end(bytes, start)),
+        _ => {
+            let end = bytes[start..]
+                .iter()
+                .position(|byte| matches!(byte, b',' | b'}' | b']') || byte.is_ascii_whitespace())
+                .map_or(bytes.len(), |offset| start + offset);
+            (end > start).then_some(end)
+        }
+    }
+}
+
+/// 여는 따옴표 위치에서 닫는 따옴표 다음 위치를 반환합니다.
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn skip_whitespace(bytes: &[u8], mut pos: usize) -> usize {
+    while bytes.get(pos).is_some_and(u8::is_ascii_whitespace) {
+        pos += 1;
+    }
+    pos
+}
+
+/// `pos` 가 속한 줄의 앞쪽 공백
+fn line_indent(content: &str, pos: usize) -> &str {
+    let line_start = content[..pos].rfind('\n').map_or(0, |index| index + 1);
+    let line = &content[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// 처음으로 들여쓴 줄의 공백을 들여쓰기 단위로 봅니다.
+fn detect_indent(content: &str) -> String {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start_matches([' ', '\t']).len()])
+        .find(|indent| !indent.is_empty())
+        .map(|indent| {
+            if indent.starts_with('\t') {
+                "\t".to_string()
+            } else {
+                indent.to_string()
+            }
+        })
+        .unwrap_or_else(|| DEFAULT_INDENT.to_string())
+}
+
+fn to_pretty(value: &Value, unit: &str, indent: &str) -> BackendResult<String> {
+    let mut buffer = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(
+        &mut buffer,
+        PrettyFormatter::with_indent(unit.as_bytes()),
+    );
+    value.serialize(&mut serializer)?;
+    let rendered = String::from_utf8_lossy(&buffer).into_owned();
+    Ok(rendered.replace('\n', &format!("\n{indent}")))
+}
+
+fn splice(content: &str, start: usize, end: usize, replacement: &str) -> String {
+    let mut updated = String::with_capacity(content.len() + replacement.len());
+    updated.push_str(&content[..start]);
+    updated.push_str(replacement);
+    updated.push_str(&content[end..]);
+    updated
+}
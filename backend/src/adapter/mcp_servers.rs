@@ -0,0 +1,458 @@
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+use super::{AdapterCapabilities, AgentAdapter, AgentKind};
+use crate::config::{McpServer, McpSettings, McpTransport};
+use crate::error::{BackendError, BackendResult};
+
+const SERVERS_KEY: &str = "mcpServers";
+const STDIO_KEYS: &[&str] = &["command", "args", "env", "cwd"];
+const URL_KEYS: &[&str] = &["url", "serverUrl", "httpUrl"];
+/// 서비스의 `enabled` 와 반대 의미로 기록되는 키
+const DISABLED_KEY: &str = "disabled";
+const AUTHORIZATION: &str = "Authorization";
+const BEARER: &str = "Bearer ";
+
+/// 최상위 `mcpServers` 맵에 서버를 이름별로 두는 형식(Claude Desktop/Code, Cursor, Windsurf, Gemini CLI)을 다룹니다.
+/// 에이전트마다 원격 서버 URL 을 담는 키만 다릅니다.
+pub struct McpServersAdapter {
+    kind: AgentKind,
+    http_url_key: &'static str,
+    sse_url_key: &'static str,
+    always_type: bool,
+    /// 원격 서버의 HTTP 헤더 맵 위치. `api_key` 는 여기의 `Authorization: Bearer` 값이 됩니다.
+    headers_path: &'static [&'static str],
+    capabilities: AdapterCapabilities,
+}
+
+impl McpServersAdapter {
+    pub const fn new(
+        kind: AgentKind,
+        http_url_key: &'static str,
+        sse_url_key: &'static str,
+    ) -> Self {
+        Self {
+            kind,
+            http_url_key,
+            sse_url_key,
+            always_type: false,
+            headers_path: &["headers"],
+            capabilities: AdapterCapabilities::FULL,
+        }
+    }
+
+    pub const fn with_capabilities(mut self, capabilities: AdapterCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// 새로 만드는 항목에도 `type` 키(`stdio`/`http`/`sse`)를 기록합니다.
+    pub const fn with_type_key(mut self) -> Self {
+        self.always_type = true;
+        self
+    }
+
+    pub const fn with_headers_path(mut self, headers_path: &'static [&'static str]) -> Self {
+        self.headers_path = headers_path;
+        self
+    }
+
+    /// 서버 이름을 키로 하는 맵을 읽습니다. `key` 는 오류 메시지에 쓰는 위치입니다.
+    pub(super) fn parse_entries(
+        &self,
+        entries: Option<&Value>,
+        key: &str,
+    ) -> BackendResult<Vec<McpServer>> {
+        match entries {
+            Some(Value::Object(entries)) => entries
+                .iter()
+                .map(|(id, entry)| self.server_from_entry(id, entry))
+                .collect(),
+            Some(Value::Null) | None => Ok(Vec::new()),
+            Some(_) => Err(BackendError::InvalidConfig(format!(
+                "'{key}' must be an object"
+            ))),
+        }
+    }
+
+    /// `previous` 의 같은 이름 항목을 바탕으로 갱신합니다.
+    pub(super) fn render_entries(
+        &self,
+        settings: &McpSettings,
+        previous: Option<&Value>,
+    ) -> Map<String, Value> {
+        let previous = previous.and_then(Value::as_object);
+        let mut entries = Map::new();
+        for server in &settings.servers {
+            let base = previous
+                .and_then(|entries| entries.get(&server.id))
+                .and_then(Value::as_object)
+                .cloned();
+            entries.insert(
+                server.id.clone(),
+                Value::Object(self.entry_from_server(server, base)),
+            );
+        }
+        entries
+    }
+
+    /// 두 전송 방식이 같은 키를 쓰면 `type` 값으로 SSE 여부를 구분합니다.
+    fn shares_url_key(&self) -> bool {
+        self.http_url_key == self.sse_url_key
+    }
+
+    fn server_from_entry(&self, id: &str, entry: &Value) -> BackendResult<McpServer> {
+        let entry = entry.as_object().ok_or_else(|| {
+            BackendError::InvalidConfig(format!("server '{id}' must be an object"))
+        })?;
+
+        let mut server = McpServer {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            transport: McpTransport::Stdio,
+            endpoint: String::new(),
+            command: None,
+            args: Vec::new(),
+            env: BTreeMap::new(),
+            cwd: None,
+            api_key: None,
+            enabled: !entry
+                .get(DISABLED_KEY)
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            extra: entry
+                .iter()
+                .filter(|(key, _)| {
+                    key.as_str() != "type"
+                        && key.as_str() != DISABLED_KEY
+                        && !STDIO_KEYS.contains(&key.as_str())
+                        && !URL_KEYS.contains(&key.as_str())
+                })
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        };
+
+        let url = |key: &str| entry.get(key).and_then(Value::as_str);
+        let marked_sse = entry.get("type").and_then(Value::as_str) == Some("sse");
+        if let Some(endpoint) = url(self.http_url_key) {
+            server.transport = if self.shares_url_key() && marked_sse {
+                McpTransport::Sse
+            } else {
+                McpTransport::StreamableHttp
+            };
+            server.endpoint = endpoint.to_string();
+        } else if let Some(endpoint) = url(self.sse_url_key) {
+            server.transport = McpTransport::Sse;
+            server.endpoint = endpoint.to_string();
+        } else {
+            let command = entry
+                .get("command")
+                .and_then(Value::as_str)
+                .ok_or_else(|| {
+                    BackendError::InvalidConfig(format!(
+                        "server '{id}' needs either '{}' or 'command'",
+                        self.http_url_key
+                    ))
+                })?;
+            server.command = Some(command.to_string());
+            server.args = string_list(id, "args", entry.get("args"))?;
+            server.env = string_map(id, "env", entry.get("env"))?;
+            server.cwd = entry.get("cwd").and_then(Value::as_str).map(str::to_string);
+        }
+        if server.is_remote() {
+            server.api_key = self.take_bearer_token(&mut server.extra);
+        }
+
+        Ok(server)
+    }
+
+    /// 기존 항목을 바탕으로 연결 정보만 갱신해, 이 형식에서만 쓰는 키는 그대로 둡니다.
+    fn entry_from_server(
+        &self,
+        server: &McpServer,
+        base: Option<Map<String, Value>>,
+    ) -> Map<String, Value> {
+        let mut entry = base.unwrap_or_default();
+        if self.always_type && !entry.contains_key("type") {
+            // 자리만 잡아 두면 아래에서 전송 방식에 맞는 값으로 채워지고, 키 순서상 맨 앞에 옵니다.
+            entry.insert("type".to_string(), Value::Null);
+        }
+        let header_order = self.header_order(&mut entry);
+        for (key, value) in &server.extra {
+            entry.insert(key.clone(), value.clone());
+        }
+        if !server.enabled {
+            entry.insert(DISABLED_KEY.to_string(), Value::Bool(true));
+        } else if entry.contains_key(DISABLED_KEY) {
+            entry.insert(DISABLED_KEY.to_string(), Value::Bool(false));
+        }
+        let had_type = entry.contains_key("type");
+        match server.transport {
+            McpTransport::Stdio => {
+                entry.shift_remove(self.http_url_key);
+                entry.shift_remove(self.sse_url_key);
+                if had_type {
+                    entry.insert("type".to_string(), Value::from("stdio"));
+                }
+                entry.insert(
+                    "command".to_string(),
+                    Value::from(server.command.clone().unwrap_or_default()),
+                );
+                // 숫자·불리언으로 적힌 값은 문자열로 읽으므로, 내용이 같으면 원래 표기를 둡니다.
+                if string_list(&server.id, "args", entry.get("args"))
+                    .ok()
+                    .as_ref()
+                    != Some(&server.args)
+                {
+                    entry.insert("args".to_string(), Value::from(server.args.clone()));
+                }
+                if server.env.is_empty() {
+                    entry.shift_remove("env");
+                } else if string_map(&server.id, "env", entry.get("env"))
+                    .ok()
+                    .as_ref()
+                    != Some(&server.env)
+                {
+                    let env = server
+                        .env
+                        .iter()
+                        .map(|(key, value)| (key.clone(), Value::from(value.clone())))
+                        .collect();
+                    entry.insert("env".to_string(), Value::Object(env));
+                }
+                match &server.cwd {
+                    Some(cwd) => {
+                        entry.insert("cwd".to_string(), Value::from(cwd.clone()));
+                    }
+                    None => {
+                        entry.shift_remove("cwd");
+                    }
+                }
+            }
+            McpTransport::StreamableHttp | McpTransport::Sse => {
+                for key in STDIO_KEYS {
+                    entry.shift_remove(*key);
+                }
+                let sse = server.transport == McpTransport::Sse;
+                if self.shares_url_key() {
+                    if sse {
+                        entry.insert("type".to_string(), Value::from("sse"));
+                    } else if had_type {
+                        entry.insert("type".to_string(), Value::from("http"));
+                    }
+                }
+                let (key, other) = if sse {
+                    (self.sse_url_key, self.http_url_key)
+                } else {
+                    (self.http_url_key, self.sse_url_key)
+                };
+                // 같은 키를 다시 쓰면 기존 자리를 유지합니다.
+                if other != key {
+                    entry.shift_remove(other);
+                }
+                entry.insert(key.to_string(), Value::from(server.endpoint.clone()));
+                self.set_bearer_token(&mut entry, server.api_key.as_deref(), &header_order);
+            }
+        }
+        entry
+    }
+
+    /// `Authorization: Bearer <키>` 헤더를 꺼내고, 비게 된 헤더 맵은 지웁니다.
+    fn take_bearer_token(&self, entry: &mut Map<String, Value>) -> Option<String> {
+        if self.headers_path.is_empty() {
+            return None;
+        }
+        let headers = object_at(entry, self.headers_path, false)?;
+        let key = headers
+            .keys()
+            .find(|key| key.eq_ignore_ascii_case(AUTHORIZATION))?
+            .clone();
+        let token = headers
+            .get(&key)
+            .and_then(Value::as_str)?
+            .strip_prefix(BEARER)?
+            .to_string();
+        headers.shift_remove(&key);
+        prune_empty(entry, self.headers_path);
+        Some(token)
+    }
+
+    fn header_order(&self, entry: &mut Map<String, Value>) -> Vec<String> {
+        if self.headers_path.is_empty() {
+            return Vec::new();
+        }
+        object_at(entry, self.headers_path, false)
+            .map(|headers| headers.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// `order` 는 기존 파일의 헤더 순서이며, 다시 넣은 `Authorization` 이 원래 자리에 오도록 합니다.
+    fn set_bearer_token(
+        &self,
+        entry: &mut Map<String, Value>,
+        api_key: Option<&str>,
+        order: &[String],
+    ) {
+        if self.headers_path.is_empty() {
+            return;
+        }
+        let Some(headers) = object_at(entry, self.headers_path, api_key.is_some()) else {
+            return;
+        };
+        let key = headers
+            .keys()
+            .find(|key| key.eq_ignore_ascii_case(AUTHORIZATION))
+            .cloned();
+        match (api_key, key) {
+            (Some(api_key), key) => {
+                let key = key.unwrap_or_else(|| AUTHORIZATION.to_string());
+                headers.insert(key, Value::from(format!("{BEARER}{api_key}")));
+                let mut reordered: Map<String, Value> = order
+                    .iter()
+                    .filter_map(|key| Some((key.clone(), headers.shift_remove(key)?)))
+                    .collect();
+                reordered.append(headers);
+                *headers = reordered;
+            }
+            // 다른 방식의 인증 헤더는 에이전트 고유 값으로 보고 둡니다.
+            (None, Some(key)) => {
+                if headers
+                    .get(&key)
+                    .and_then(Value::as_str)
+                    .is_some_and(|value| value.starts_with(BEARER))
+                {
+                    headers.shift_remove(&key);
+                    prune_empty(entry, self.headers_path);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+impl AgentAdapter for McpServersAdapter {
+    fn kind(&self) -> AgentKind {
+        self.kind
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        self.capabilities
+    }
+
+    fn detect(&self, content: &str) -> bool {
+        serde_json::from_str::<Value>(content)
+            .ok()
+            .and_then(|value| value.get(SERVERS_KEY).map(Value::is_object))
+            .unwrap_or(false)
+    }
+
+    fn parse(&self, content: &str) -> BackendResult<McpSettings> {
+        let root = parse_root(content)?;
+        Ok(McpSettings {
+            servers: self.parse_entries(root.get(SERVERS_KEY), SERVERS_KEY)?,
+            ..Default::default()
+        })
+    }
+
+    fn render(&self, settings: &McpSettings, existing: Option<&str>) -> BackendResult<String> {
+        let mut root = match existing {
+            Some(content) if !content.trim().is_empty() => parse_root(content)?,
+            _ => Map::new(),
+        };
+        let entries = self.render_entries(settings, root.get(SERVERS_KEY));
+        root.insert(SERVERS_KEY.to_string(), Value::Object(entries));
+
+        let mut rendered = serde_json::to_string_pretty(&Value::Object(root))?;
+        if existing.is_some_and(|content| content.ends_with('\n')) {
+            rendered.push('\n');
+        }
+        Ok(rendered)
+    }
+}
+
+/// `path` 위치의 맵. `create` 이면 없는 중간 맵을 만듭니다.
+fn object_at<'a>(
+    entry: &'a mut Map<String, Value>,
+    path: &[&str],
+    create: bool,
+) -> Option<&'a mut Map<String, Value>> {
+    let mut current = entry;
+    for key in path {
+        if create && !current.contains_key(*key) {
+            current.insert(key.to_string(), Value::Object(Map::new()));
+        }
+        current = current.get_mut(*key)?.as_object_mut()?;
+    }
+    Some(current)
+}
+
+/// 헤더를 지운 뒤 비어 있는 맵을 안쪽부터 지웁니다.
+fn prune_empty(entry: &mut Map<String, Value>, path: &[&str]) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let Some(parent) = object_at(entry, parents, false) else {
+        return;
+    };
+    if parent
+        .get(*last)
+        .and_then(Value::as_object)
+        .is_some_and(Map::is_empty)
+    {
+        parent.shift_remove(*last);
+        prune_empty(entry, parents);
+    }
+}
+
+fn parse_root(content: &str) -> BackendResult<Map<String, Value>> {
+    match serde_json::from_str::<Value>(content)? {
+        Value::Object(root) => Ok(root),
+        _ => Err(BackendError::InvalidConfig(
+            "top-level value must be an object".to_string(),
+        )),
+    }
+}
+
+/// 문자열이 아닌 스칼라(숫자, 불리언)는 문자열로 바꿔 읽고, 그 밖의 값은 거부합니다.
+fn scalar_string(id: &str, key: &str, value: &Value) -> BackendResult<String> {
+    match value {
+        Value::String(text) => Ok(text.clone()),
+        Value::Number(number) => Ok(number.to_string()),
+        Value::Bool(flag) => Ok(flag.to_string()),
+        _ => Err(BackendError::InvalidConfig(format!(
+            "server '{id}' has a non-scalar value in '{key}'"
+        ))),
+    }
+}
+
+fn string_list(id: &str, key: &str, value: Option<&Value>) -> BackendResult<Vec<String>> {
+    match value {
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| scalar_string(id, key, item))
+            .collect(),
+        Some(Value::Null) | None => Ok(Vec::new()),
+        Some(_) => Err(BackendError::InvalidConfig(format!(
+            "server '{id}': '{key}' must be an array"
+        ))),
+    }
+}
+
+fn string_map(
+    id: &str,
+    key: &str,
+    value: Option<&Value>,
+) -> BackendResult<BTreeMap<String, String>> {
+    match value {
+        Some(Value::Object(items)) => items
+            .iter()
+            .map(|(name, item)| Ok((name.clone(), scalar_string(id, key, item)?)))
+            .collect(),
+        Some(Value::Null) | None => Ok(BTreeMap::new()),
+        Some(_) => Err(BackendError::InvalidConfig(format!(
+            "server '{id}': '{key}' must be an object"
+        ))),
+    }
+}
@@ -0,0 +1,218 @@
+mod codex;
+mod continue_dev;
+mod jsonc;
+mod mcp_servers;
+mod native;
+mod vscode;
+mod zed;
+
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::McpSettings;
+use crate::error::{BackendError, BackendResult};
+
+pub use codex::CodexAdapter;
+pub use continue_dev::ContinueAdapter;
+pub use mcp_servers::McpServersAdapter;
+pub use native::NativeAdapter;
+pub use vscode::VsCodeAdapter;
+pub use zed::ZedAdapter;
+
+static CLAUDE_DESKTOP: McpServersAdapter =
+    McpServersAdapter::new(AgentKind::ClaudeDesktop, "url", "url").with_capabilities(
+        AdapterCapabilities {
+            streamable_http: false,
+            sse: false,
+            cwd: false,
+            ..AdapterCapabilities::FULL
+        },
+    );
+static CLAUDE_CODE: McpServersAdapter = McpServersAdapter::new(AgentKind::ClaudeCode, "url", "url")
+    .with_capabilities(AdapterCapabilities {
+        cwd: false,
+        ..AdapterCapabilities::FULL
+    });
+static CURSOR: McpServersAdapter = McpServersAdapter::new(AgentKind::Cursor, "url", "url")
+    .with_capabilities(AdapterCapabilities {
+        cwd: false,
+        ..AdapterCapabilities::FULL
+    });
+static WINDSURF: McpServersAdapter =
+    McpServersAdapter::new(AgentKind::Windsurf, "serverUrl", "serverUrl").with_capabilities(
+        AdapterCapabilities {
+            cwd: false,
+            ..AdapterCapabilities::FULL
+        },
+    );
+static GEMINI_CLI: McpServersAdapter =
+    McpServersAdapter::new(AgentKind::GeminiCli, "httpUrl", "url");
+
+/// 에이전트 설정 형식이 표현할 수 있는 항목입니다. 동기화는 지원하지 않는 항목을
+/// 기록하지 않도록 `sync::conform_settings` 에서 변환하거나 생략합니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AdapterCapabilities {
+    pub stdio: bool,
+    pub streamable_http: bool,
+    pub sse: bool,
+    pub env: bool,
+    pub cwd: bool,
+    /// 비활성 서버를 파일에 남길 수 있는지 여부. 없으면 비활성 서버는 생략합니다.
+    pub enabled_flag: bool,
+}
+
+impl AdapterCapabilities {
+    pub const FULL: Self = Self {
+        stdio: true,
+        streamable_http: true,
+        sse: true,
+        env: true,
+        cwd: true,
+        enabled_flag: true,
+    };
+}
+
+/// 에이전트별 설정 파일 형식을 `McpSettings` 와 상호 변환합니다.
+pub trait AgentAdapter: Send + Sync {
+    fn kind(&self) -> AgentKind;
+
+    fn capabilities(&self) -> AdapterCapabilities;
+
+    /// 파일 내용이 이 어댑터가 다루는 형식인지 판별합니다.
+    fn detect(&self, content: &str) -> bool;
+
+    fn parse(&self, content: &str) -> BackendResult<McpSettings>;
+
+    /// `existing` 에는 현재 파일 내용이 전달되며, 어댑터는 MCP 와 무관한 부분을 보존해야 합니다.
+    fn render(&self, settings: &McpSettings, existing: Option<&str>) -> BackendResult<String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentKind {
+    Native,
+    ClaudeDesktop,
+    ClaudeCode,
+    Cursor,
+    Windsurf,
+    GeminiCli,
+    #[serde(rename = "vscode")]
+    VsCode,
+    Codex,
+    Continue,
+    Zed,
+}
+
+impl AgentKind {
+    pub const ALL: &'static [AgentKind] = &[
+        AgentKind::Native,
+        AgentKind::ClaudeDesktop,
+        AgentKind::ClaudeCode,
+        AgentKind::Cursor,
+        AgentKind::Windsurf,
+        AgentKind::GeminiCli,
+        AgentKind::VsCode,
+        AgentKind::Codex,
+        AgentKind::Continue,
+        AgentKind::Zed,
+    ];
+
+    pub fn adapter(self) -> &'static dyn AgentAdapter {
+        match self {
+            AgentKind::Native => &NativeAdapter,
+            AgentKind::ClaudeDesktop => &CLAUDE_DESKTOP,
+            AgentKind::ClaudeCode => &CLAUDE_CODE,
+            AgentKind::Cursor => &CURSOR,
+            AgentKind::Windsurf => &WINDSURF,
+            AgentKind::GeminiCli => &GEMINI_CLI,
+            AgentKind::VsCode => &VsCodeAdapter::MCP_FILE,
+            AgentKind::Codex => &CodexAdapter,
+            AgentKind::Continue => &ContinueAdapter,
+            AgentKind::Zed => &ZedAdapter,
+        }
+    }
+
+    /// 설정 파일 `path` 에 맞는 어댑터. 한 에이전트가 여러 파일 형식을 쓰면 파일 이름으로 고릅니다.
+    pub fn adapter_for(self, path: &Path) -> &'static dyn AgentAdapter {
+        match self {
+            AgentKind::VsCode => VsCodeAdapter::for_path(path),
+            _ => self.adapter(),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AgentKind::Native => "native",
+            AgentKind::ClaudeDesktop => "claude_desktop",
+            AgentKind::ClaudeCode => "claude_code",
+            AgentKind::Cursor => "cursor",
+            AgentKind::Windsurf => "windsurf",
+            AgentKind::GeminiCli => "gemini_cli",
+            AgentKind::VsCode => "vscode",
+            AgentKind::Codex => "codex",
+            AgentKind::Continue => "continue",
+            AgentKind::Zed => "zed",
+        }
+    }
+
+    /// 도구 디렉터리/등록 이름 중 이 종류로 간주하는 이름들입니다.
+    pub fn tool_names(self) -> &'static [&'static str] {
+        match self {
+            AgentKind::Native => &[],
+            AgentKind::ClaudeDesktop => &["claude", "claude-desktop", "claude_desktop"],
+            AgentKind::ClaudeCode => &["claude-code", "claude_code"],
+            AgentKind::Cursor => &["cursor"],
+            AgentKind::Windsurf => &["windsurf"],
+            AgentKind::GeminiCli => &["gemini", "gemini-cli", "gemini_cli"],
+            AgentKind::VsCode => &["vscode", "vs-code", "code"],
+            AgentKind::Codex => &["codex", "codex-cli"],
+            AgentKind::Continue => &["continue"],
+            AgentKind::Zed => &["zed"],
+        }
+    }
+
+    pub fn from_tool_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        AgentKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.tool_names().contains(&name.as_str()))
+    }
+}
+
+impl fmt::Display for AgentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AgentKind {
+    type Err = BackendError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        AgentKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.as_str() == value)
+            .ok_or_else(|| BackendError::Other(format!("unknown agent kind '{value}'")))
+    }
+}
+
+/// 도구 이름으로 먼저 어댑터를 고르되, 파일 내용이 그 형식과 맞지 않으면 시그니처로 판별합니다.
+pub fn select_adapter(tool_name: &str, content: &str) -> AgentKind {
+    let by_name = AgentKind::from_tool_name(tool_name);
+    if let Some(kind) = by_name {
+        if content.trim().is_empty() || kind.adapter().detect(content) {
+            return kind;
+        }
+    }
+    AgentKind::ALL
+        .iter()
+        .copied()
+        .find(|kind| kind.adapter().detect(content))
+        .or(by_name)
+        .unwrap_or(AgentKind::Native)
+}
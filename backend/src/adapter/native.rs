@@ -0,0 +1,33 @@
+use serde_json::Value;
+
+use super::{AdapterCapabilities, AgentAdapter, AgentKind};
+use crate::config::McpSettings;
+use crate::error::BackendResult;
+
+/// 이 서비스 고유의 `McpSettings` JSON 형식을 그대로 읽고 씁니다.
+pub struct NativeAdapter;
+
+impl AgentAdapter for NativeAdapter {
+    fn kind(&self) -> AgentKind {
+        AgentKind::Native
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::FULL
+    }
+
+    fn detect(&self, content: &str) -> bool {
+        serde_json::from_str::<Value>(content)
+            .ok()
+            .and_then(|value| value.get("servers").map(Value::is_array))
+            .unwrap_or(false)
+    }
+
+    fn parse(&self, content: &str) -> BackendResult<McpSettings> {
+        Ok(serde_json::from_str(content)?)
+    }
+
+    fn render(&self, settings: &McpSettings, _existing: Option<&str>) -> BackendResult<String> {
+        Ok(serde_json::to_string_pretty(settings)?)
+    }
+}
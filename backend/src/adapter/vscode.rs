@@ -0,0 +1,96 @@
+use std::ffi::OsStr;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use super::{jsonc, AdapterCapabilities, AgentAdapter, AgentKind, McpServersAdapter};
+use crate::config::McpSettings;
+use crate::error::BackendResult;
+
+const SETTINGS_FILE_NAME: &str = "settings.json";
+const SETTINGS_PATH: &[&str] = &["mcp", "servers"];
+const MCP_FILE_PATH: &[&str] = &["servers"];
+
+static ENTRIES: McpServersAdapter =
+    McpServersAdapter::new(AgentKind::VsCode, "url", "url").with_type_key();
+
+/// VS Code 의 `settings.json`(`mcp.servers`)과 `mcp.json`(`servers`)을 다룹니다.
+/// 두 파일 모두 JSONC 이므로, 쓸 때는 서버 맵만 교체해 주석과 `inputs` 등 나머지 내용을 보존합니다.
+pub struct VsCodeAdapter {
+    servers_path: &'static [&'static str],
+}
+
+impl VsCodeAdapter {
+    /// 사용자·워크스페이스의 `mcp.json`. 파일 이름을 모를 때의 기본값입니다.
+    pub const MCP_FILE: Self = Self {
+        servers_path: MCP_FILE_PATH,
+    };
+    pub const SETTINGS: Self = Self {
+        servers_path: SETTINGS_PATH,
+    };
+
+    /// 어느 파일인지는 내용이 아니라 파일 이름으로 정합니다. `inputs` 만 든 `mcp.json` 도
+    /// `servers` 를 씁니다.
+    pub fn for_path(path: &Path) -> &'static Self {
+        if path.file_name() == Some(OsStr::new(SETTINGS_FILE_NAME)) {
+            &Self::SETTINGS
+        } else {
+            &Self::MCP_FILE
+        }
+    }
+
+    fn servers_at<'a>(root: &'a Map<String, Value>, path: &[&str]) -> Option<&'a Value> {
+        let (first, rest) = path.split_first()?;
+        rest.iter()
+            .try_fold(root.get(*first)?, |value, key| value.get(key))
+    }
+}
+
+impl AgentAdapter for VsCodeAdapter {
+    fn kind(&self) -> AgentKind {
+        AgentKind::VsCode
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities {
+            enabled_flag: false,
+            ..AdapterCapabilities::FULL
+        }
+    }
+
+    fn detect(&self, content: &str) -> bool {
+        jsonc::parse_object(content)
+            .map(|root| {
+                [SETTINGS_PATH, MCP_FILE_PATH]
+                    .iter()
+                    .any(|path| Self::servers_at(&root, path).is_some_and(Value::is_object))
+            })
+            .unwrap_or(false)
+    }
+
+    fn parse(&self, content: &str) -> BackendResult<McpSettings> {
+        let root = jsonc::parse_object(content)?;
+        let path = self.servers_path;
+        Ok(McpSettings {
+            servers: ENTRIES.parse_entries(Self::servers_at(&root, path), &path.join("."))?,
+            ..Default::default()
+        })
+    }
+
+    fn render(&self, settings: &McpSettings, existing: Option<&str>) -> BackendResult<String> {
+        let content = existing.filter(|content| !content.trim().is_empty());
+        let root = match content {
+            Some(content) => jsonc::parse_object(content)?,
+            None => Map::new(),
+        };
+        let path = self.servers_path;
+        let entries =
+            Value::Object(ENTRIES.render_entries(settings, Self::servers_at(&root, path)));
+        match content {
+            Some(content) => jsonc::replace_value(content, path, &entries),
+            None => jsonc::to_document(&path.iter().rev().fold(entries, |value, key| {
+                Value::Object(Map::from_iter([(key.to_string(), value)]))
+            })),
+        }
+    }
+}
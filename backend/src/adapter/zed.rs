@@ -0,0 +1,175 @@
+use serde_json::{Map, Value};
+
+use super::{jsonc, AdapterCapabilities, AgentAdapter, AgentKind, McpServersAdapter};
+use crate::config::McpSettings;
+use crate::error::BackendResult;
+
+const SERVERS_KEY: &str = "context_servers";
+const NESTED_KEYS: &[&str] = &["args", "env"];
+
+static ENTRIES: McpServersAdapter = McpServersAdapter::new(AgentKind::Zed, "url", "url");
+
+/// Zed `settings.json` 의 `context_servers` 를 다룹니다. 실행 정보는
+/// `command: { path, args, env }` 형태로 중첩되며, 예전의 평평한 형태도 읽습니다.
+/// 쓸 때는 기존 항목의 형태를 따르고, 새 항목은 중첩 형태로 씁니다.
+/// 확장이 제공하는 항목(`"source": "extension"`)처럼 `command` 도 URL 도 없는 항목은
+/// 서버로 읽지 않고, 쓸 때 원래 자리에 그대로 남깁니다.
+pub struct ZedAdapter;
+
+impl ZedAdapter {
+    /// 중첩된 `command` 를 `mcpServers` 형식과 같은 평평한 항목으로 펼칩니다.
+    fn flatten(entries: &Value) -> Value {
+        let Value::Object(entries) = entries else {
+            return entries.clone();
+        };
+        let flattened = entries
+            .iter()
+            .map(|(id, entry)| {
+                let entry = match entry {
+                    Value::Object(fields) if is_nested(fields) => {
+                        let mut flat = Map::new();
+                        for (key, value) in fields {
+                            match (key.as_str(), value) {
+                                ("command", Value::Object(command)) => {
+                                    if let Some(path) = command.get("path") {
+                                        flat.insert("command".to_string(), path.clone());
+                                    }
+                                    for key in NESTED_KEYS {
+                                        if let Some(value) = command.get(*key) {
+                                            flat.insert(key.to_string(), value.clone());
+                                        }
+                                    }
+                                }
+                                _ => {
+                                    flat.insert(key.clone(), value.clone());
+                                }
+                            }
+                        }
+                        Value::Object(flat)
+                    }
+                    other => other.clone(),
+                };
+                (id.clone(), entry)
+            })
+            .collect();
+        Value::Object(flattened)
+    }
+
+    fn nest(mut entry: Map<String, Value>) -> Map<String, Value> {
+        let Some(path) = entry.get("command").cloned() else {
+            return entry;
+        };
+        let mut command = Map::new();
+        command.insert("path".to_string(), path);
+        for key in NESTED_KEYS {
+            if let Some(value) = entry.shift_remove(*key) {
+                command.insert(key.to_string(), value);
+            }
+        }
+        entry.insert("command".to_string(), Value::Object(command));
+        entry
+    }
+}
+
+impl AgentAdapter for ZedAdapter {
+    fn kind(&self) -> AgentKind {
+        AgentKind::Zed
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities {
+            sse: false,
+            cwd: false,
+            enabled_flag: false,
+            ..AdapterCapabilities::FULL
+        }
+    }
+
+    fn detect(&self, content: &str) -> bool {
+        jsonc::parse_object(content)
+            .map(|root| root.get(SERVERS_KEY).is_some_and(Value::is_object))
+            .unwrap_or(false)
+    }
+
+    fn parse(&self, content: &str) -> BackendResult<McpSettings> {
+        let root = jsonc::parse_object(content)?;
+        let entries = root
+            .get(SERVERS_KEY)
+            .map(|entries| match Self::flatten(entries) {
+                Value::Object(entries) => Value::Object(
+                    entries
+                        .into_iter()
+                        .filter(|(_, entry)| !is_opaque(entry))
+                        .collect(),
+                ),
+                other => other,
+            });
+        Ok(McpSettings {
+            servers: ENTRIES.parse_entries(entries.as_ref(), SERVERS_KEY)?,
+            ..Default::default()
+        })
+    }
+
+    fn render(&self, settings: &McpSettings, existing: Option<&str>) -> BackendResult<String> {
+        let content = existing.filter(|content| !content.trim().is_empty());
+        let root = match content {
+            Some(content) => jsonc::parse_object(content)?,
+            None => Map::new(),
+        };
+        let previous = root.get(SERVERS_KEY);
+        let flattened = previous.map(Self::flatten);
+        let mut entries: Map<String, Value> = ENTRIES
+            .render_entries(settings, flattened.as_ref())
+            .into_iter()
+            .map(|(id, entry)| {
+                let flat_before = previous
+                    .and_then(|entries| entries.get(&id))
+                    .and_then(Value::as_object)
+                    .is_some_and(|fields| !is_nested(fields));
+                let entry = match entry {
+                    Value::Object(fields) if !flat_before => Value::Object(Self::nest(fields)),
+                    other => other,
+                };
+                (id, entry)
+            })
+            .collect();
+        if let Some(Value::Object(previous)) = previous {
+            keep_opaque_entries(&mut entries, previous);
+        }
+        let entries = Value::Object(entries);
+        match content {
+            Some(content) => jsonc::replace_value(content, &[SERVERS_KEY], &entries),
+            None => jsonc::to_document(&Value::Object(Map::from_iter([(
+                SERVERS_KEY.to_string(),
+                entries,
+            )]))),
+        }
+    }
+}
+
+fn is_nested(fields: &Map<String, Value>) -> bool {
+    fields.get("command").is_some_and(Value::is_object)
+}
+
+/// 동기화 대상이 아닌 항목. 확장 항목은 `settings` 만 가지고 있습니다.
+fn is_opaque(entry: &Value) -> bool {
+    entry
+        .as_object()
+        .is_some_and(|fields| !fields.contains_key("command") && !fields.contains_key("url"))
+}
+
+/// 기존 파일의 불투명 항목을 앞 항목 바로 뒤(없으면 맨 앞)에 다시 넣습니다.
+fn keep_opaque_entries(entries: &mut Map<String, Value>, previous: &Map<String, Value>) {
+    for (index, (id, entry)) in previous.iter().enumerate() {
+        if !is_opaque(entry) || entries.contains_key(id) {
+            continue;
+        }
+        let position = previous
+            .keys()
+            .take(index)
+            .rev()
+            .find_map(|before| entries.keys().position(|key| key == before))
+            .map_or(0, |position| position + 1);
+        entries.shift_insert(position, id.clone(), entry.clone());
+    }
+}
@@ -1,70 +1,375 @@
-use axum::extract::State;
-use axum::routing::{get, post};
+use axum::extract::{FromRequestParts, Path, Query, State};
+use axum::http::request::Parts;
+use axum::http::{header, StatusCode};
+use axum::routing::{get, post, put};
 use axum::{Json, Router};
+use serde::Deserialize;
 
+use crate::audit::{self, SecretFinding, SecretMigration};
 use crate::config::{
-    ImportRecommendedRequest, MasterConfigResponse, McpSettings, RecommendedServer, SyncRequest,
-    SyncSummary, ToolConfiguration, UpdateMasterRequest,
+    AssignProfileRequest, CreateProfileRequest, ImportRecommendedRequest, MasterConfigResponse,
+    MasterRevision, McpSettings, Profile, RecommendedServer, ResolveConflictRequest,
+    RestoreRequest, RevisionMeta, RevisionSource, RollbackRequest, SyncRequest, SyncResponse,
+    SyncStatus, SyncSummary, ToolConfiguration, UpdateEnvModeRequest, UpdateMasterRequest,
+    UpdatePolicyRequest, UpdateProfileRequest,
 };
 use crate::db::Database;
+use crate::diff::{self, ToolDiff};
 use crate::error::{BackendError, BackendResult};
+use crate::redact::{self, Redact};
+use crate::settings::Settings;
 use crate::sync;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: std::sync::Arc<Database>,
+    pub settings: std::sync::Arc<Settings>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RevealQuery {
+    #[serde(default)]
+    reveal: bool,
+}
+
+/// `?reveal=true` 요청 여부. 설정된 관리자 토큰을 `Authorization: Bearer` 로 보낸 경우에만
+/// 허용하며, 그 밖의 응답은 자격 증명을 가립니다.
+pub struct Reveal(bool);
+
+impl Reveal {
+    fn apply<T: Redact>(&self, value: T) -> T {
+        if self.0 {
+            value
+        } else {
+            value.redacted()
+        }
+    }
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for Reveal {
+    type Rejection = BackendError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<RevealQuery>::try_from_uri(&parts.uri)
+            .map_err(|err| BackendError::InvalidConfig(err.to_string()))?;
+        if !query.reveal {
+            return Ok(Reveal(false));
+        }
+        let provided = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        state.settings.authorize_reveal(provided)?;
+        Ok(Reveal(true))
+    }
 }
 
 pub fn router(state: AppState) -> Router {
     Router::new()
         .route("/api/tools", get(list_tools))
         .route("/api/tools/rescan", post(rescan_tools))
+        .route("/api/tools/:name/diff", get(tool_diff))
+        .route("/api/tools/:name/restore", post(restore_tool))
+        .route("/api/tools/:name/policy", put(update_tool_policy))
+        .route("/api/tools/:name/env-mode", put(update_tool_env_mode))
+        .route("/api/tools/:name/profile", put(update_tool_profile))
+        .route("/api/tools/:name/resolve", post(resolve_tool_conflict))
         .route(
             "/api/config/master",
             get(get_master_config).post(update_master_config),
         )
+        .route("/api/config/master/revisions", get(list_master_revisions))
+        .route(
+            "/api/config/master/revisions/:id/rollback",
+            post(rollback_master_config),
+        )
+        .route("/api/profiles", get(list_profiles).post(create_profile))
+        .route(
+            "/api/profiles/:name",
+            get(get_profile).put(update_profile).delete(delete_profile),
+        )
+        .route("/api/profiles/:name/revisions", get(list_profile_revisions))
+        .route(
+            "/api/profiles/:name/revisions/:id/rollback",
+            post(rollback_profile),
+        )
         .route("/api/config/recommended", get(get_recommended_servers))
         .route("/api/config/master/import", post(import_recommended_server))
         .route("/api/sync", post(sync_tools))
         .route("/api/sync/history", get(sync_history))
+        .route("/api/audit/secrets", get(audit_secrets))
+        .route("/api/audit/secrets/migrate", post(migrate_secrets))
         .with_state(state)
 }
 
-async fn list_tools(State(state): State<AppState>) -> BackendResult<Json<Vec<ToolConfiguration>>> {
+async fn list_tools(
+    State(state): State<AppState>,
+    reveal: Reveal,
+) -> BackendResult<Json<Vec<ToolConfiguration>>> {
+    let secrets = state.db.load_secrets()?;
     let tools = state
         .db
         .list_tools()?
         .into_iter()
-        .map(|(name, path)| {
-            let settings =
-                sync::read_settings_from_file(&path).unwrap_or_else(|_| McpSettings::default());
-            ToolConfiguration::new(name, path.to_string_lossy(), settings)
+        .map(|record| {
+            sync::load_tool(&record, &secrets).unwrap_or_else(|_| {
+                let kind = record.agent_kind("");
+                let mut tool = ToolConfiguration::new(
+                    record.name,
+                    kind,
+                    record.config_path.to_string_lossy(),
+                    McpSettings::default(),
+                );
+                tool.project = record.project;
+                tool.policy = record.policy;
+                tool.env_mode = record.env_mode;
+                tool.profile = record.profile;
+                tool
+            })
         })
+        .map(|tool| reveal.apply(tool))
         .collect();
     Ok(Json(tools))
 }
 
 async fn rescan_tools(
     State(state): State<AppState>,
+    reveal: Reveal,
 ) -> BackendResult<Json<Vec<ToolConfiguration>>> {
-    let tools = sync::discover_tools(&state.db)?;
-    Ok(Json(tools))
+    let tools = sync::discover_tools(&state.db, &state.settings)?;
+    Ok(Json(
+        tools.into_iter().map(|tool| reveal.apply(tool)).collect(),
+    ))
+}
+
+async fn tool_diff(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    reveal: Reveal,
+) -> BackendResult<Json<ToolDiff>> {
+    let record = state
+        .db
+        .find_tool(&name)?
+        .ok_or_else(|| BackendError::NotFound(format!("tool '{name}'")))?;
+    let tool = sync::load_tool(&record, &state.db.load_secrets()?)?;
+    let master = state.db.profile_settings(record.profile.as_deref())?;
+    Ok(Json(reveal.apply(diff::diff_tool(&tool, &master)?)))
+}
+
+async fn restore_tool(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<RestoreRequest>,
+) -> BackendResult<Json<SyncSummary>> {
+    let summary = sync::restore_tool(&state.db, &name, request.sync_id)?;
+    Ok(Json(summary))
+}
+
+async fn update_tool_policy(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    reveal: Reveal,
+    Json(request): Json<UpdatePolicyRequest>,
+) -> BackendResult<Json<ToolConfiguration>> {
+    state.db.set_tool_policy(&name, request.policy)?;
+    let record = state
+        .db
+        .find_tool(&name)?
+        .ok_or_else(|| BackendError::NotFound(format!("tool '{name}'")))?;
+    let tool = sync::load_tool(&record, &state.db.load_secrets()?)?;
+    Ok(Json(reveal.apply(tool)))
+}
+
+async fn update_tool_env_mode(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    reveal: Reveal,
+    Json(request): Json<UpdateEnvModeRequest>,
+) -> BackendResult<Json<ToolConfiguration>> {
+    state.db.set_tool_env_mode(&name, request.env_mode)?;
+    let record = state
+        .db
+        .find_tool(&name)?
+        .ok_or_else(|| BackendError::NotFound(format!("tool '{name}'")))?;
+    let tool = sync::load_tool(&record, &state.db.load_secrets()?)?;
+    Ok(Json(reveal.apply(tool)))
+}
+
+async fn update_tool_profile(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    reveal: Reveal,
+    Json(request): Json<AssignProfileRequest>,
+) -> BackendResult<Json<ToolConfiguration>> {
+    state.db.set_tool_profile(&name, &request.profile)?;
+    let record = state
+        .db
+        .find_tool(&name)?
+        .ok_or_else(|| BackendError::NotFound(format!("tool '{name}'")))?;
+    let tool = sync::load_tool(&record, &state.db.load_secrets()?)?;
+    Ok(Json(reveal.apply(tool)))
+}
+
+async fn resolve_tool_conflict(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<ResolveConflictRequest>,
+) -> BackendResult<Json<SyncSummary>> {
+    let record = state
+        .db
+        .find_tool(&name)?
+        .ok_or_else(|| BackendError::NotFound(format!("tool '{name}'")))?;
+    let summary = sync::resolve_conflict(&state.db, &record, &request.server, request.take)?;
+    Ok(Json(summary))
 }
 
 async fn get_master_config(
     State(state): State<AppState>,
+    reveal: Reveal,
 ) -> BackendResult<Json<MasterConfigResponse>> {
     let config = state.db.ensure_master_config()?;
-    Ok(Json(config))
+    Ok(Json(reveal.apply(config)))
 }
 
 async fn update_master_config(
     State(state): State<AppState>,
+    reveal: Reveal,
     Json(payload): Json<UpdateMasterRequest>,
 ) -> BackendResult<Json<MasterConfigResponse>> {
-    state.db.upsert_master_config(&payload.settings)?;
+    // 조회 응답에서 가린 값을 그대로 돌려보낸 항목은 저장된 원래 값을 유지합니다.
+    let mut settings = payload.settings;
+    redact::restore_masked(&mut settings, &state.db.ensure_master_config()?.settings);
+    state.db.upsert_master_config(
+        &settings,
+        &RevisionMeta::new(RevisionSource::Api, payload.author),
+    )?;
     let config = state.db.ensure_master_config()?;
-    Ok(Json(config))
+    Ok(Json(reveal.apply(config)))
+}
+
+async fn list_master_revisions(
+    State(state): State<AppState>,
+    reveal: Reveal,
+) -> BackendResult<Json<Vec<MasterRevision>>> {
+    let revisions = state.db.list_master_revisions(50)?;
+    Ok(Json(
+        revisions
+            .into_iter()
+            .map(|revision| reveal.apply(revision))
+            .collect(),
+    ))
+}
+
+async fn rollback_master_config(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    reveal: Reveal,
+    Json(request): Json<RollbackRequest>,
+) -> BackendResult<Json<MasterConfigResponse>> {
+    let config = state
+        .db
+        .rollback_master_config(id, RevisionMeta::new(RevisionSource::Api, request.author))?;
+    Ok(Json(reveal.apply(config)))
+}
+
+async fn list_profiles(
+    State(state): State<AppState>,
+    reveal: Reveal,
+) -> BackendResult<Json<Vec<Profile>>> {
+    let profiles = state.db.list_profiles()?;
+    Ok(Json(
+        profiles
+            .into_iter()
+            .map(|profile| reveal.apply(profile))
+            .collect(),
+    ))
+}
+
+fn find_profile(state: &AppState, name: &str) -> BackendResult<Profile> {
+    state
+        .db
+        .get_profile(name)?
+        .ok_or_else(|| BackendError::NotFound(format!("profile '{name}'")))
+}
+
+async fn create_profile(
+    State(state): State<AppState>,
+    reveal: Reveal,
+    Json(request): Json<CreateProfileRequest>,
+) -> BackendResult<(StatusCode, Json<Profile>)> {
+    let settings = match request.settings {
+        Some(settings) => settings,
+        None => state.db.ensure_master_config()?.settings,
+    };
+    state.db.create_profile(&request.name, &settings)?;
+    let profile = find_profile(&state, &request.name)?;
+    Ok((StatusCode::CREATED, Json(reveal.apply(profile))))
+}
+
+async fn get_profile(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    reveal: Reveal,
+) -> BackendResult<Json<Profile>> {
+    Ok(Json(reveal.apply(find_profile(&state, &name)?)))
+}
+
+async fn update_profile(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    reveal: Reveal,
+    Json(request): Json<UpdateProfileRequest>,
+) -> BackendResult<Json<Profile>> {
+    let mut settings = request.settings;
+    redact::restore_masked(&mut settings, &state.db.profile_settings(Some(&name))?);
+    state.db.save_profile(
+        &name,
+        &settings,
+        &RevisionMeta::new(RevisionSource::Api, request.author),
+    )?;
+    Ok(Json(reveal.apply(find_profile(&state, &name)?)))
+}
+
+async fn list_profile_revisions(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    reveal: Reveal,
+) -> BackendResult<Json<Vec<MasterRevision>>> {
+    find_profile(&state, &name)?;
+    let revisions = state.db.list_profile_revisions(&name, 50)?;
+    Ok(Json(
+        revisions
+            .into_iter()
+            .map(|revision| reveal.apply(revision))
+            .collect(),
+    ))
+}
+
+async fn rollback_profile(
+    State(state): State<AppState>,
+    Path((name, id)): Path<(String, i64)>,
+    reveal: Reveal,
+    Json(request): Json<RollbackRequest>,
+) -> BackendResult<Json<Profile>> {
+    state.db.rollback_profile(
+        &name,
+        id,
+        RevisionMeta::new(RevisionSource::Api, request.author),
+    )?;
+    Ok(Json(reveal.apply(find_profile(&state, &name)?)))
+}
+
+async fn delete_profile(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> BackendResult<StatusCode> {
+    state.db.remove_profile(&name)?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
 async fn get_recommended_servers(
@@ -76,6 +381,7 @@ async fn get_recommended_servers(
 
 async fn import_recommended_server(
     State(state): State<AppState>,
+    reveal: Reveal,
     Json(payload): Json<ImportRecommendedRequest>,
 ) -> BackendResult<Json<MasterConfigResponse>> {
     let server = state
@@ -87,40 +393,79 @@ async fn import_recommended_server(
 
     let mut master = state.db.ensure_master_config()?.settings;
     let enabled = payload.enabled.unwrap_or(server.default_enabled);
-    master.apply_recommended_server(&server, enabled);
+    master.apply_recommended_server(&server, enabled)?;
 
-    state.db.upsert_master_config(&master)?;
+    state.db.upsert_master_config(
+        &master,
+        &RevisionMeta::new(RevisionSource::Import, None)
+            .with_message(format!("imported recommended server '{}'", server.id)),
+    )?;
     let updated = state.db.ensure_master_config()?;
-    Ok(Json(updated))
+    Ok(Json(reveal.apply(updated)))
 }
 
 async fn sync_tools(
     State(state): State<AppState>,
+    reveal: Reveal,
     Json(request): Json<SyncRequest>,
-) -> BackendResult<Json<Vec<SyncSummary>>> {
-    let master = state.db.ensure_master_config()?.settings;
+) -> BackendResult<(StatusCode, Json<SyncResponse>)> {
     let tools = if let Some(tool_name) = request.tool {
         state
             .db
             .list_tools()?
             .into_iter()
-            .filter(|(name, _)| *name == tool_name)
+            .filter(|record| record.name == tool_name || record.agent == tool_name)
             .collect::<Vec<_>>()
     } else {
         state.db.list_tools()?
     };
 
+    if request.dry_run {
+        let mut plans = Vec::new();
+        for record in tools {
+            let plan = sync::plan_registered_tool(&state.db, &record)?;
+            plans.push(reveal.apply(plan));
+        }
+        let status = if plans.iter().any(|plan| plan.error.is_some()) {
+            StatusCode::MULTI_STATUS
+        } else {
+            StatusCode::OK
+        };
+        return Ok((status, Json(SyncResponse::Planned(plans))));
+    }
+
     let mut summaries = Vec::new();
-    for (name, path) in tools {
-        let settings = sync::read_settings_from_file(&path)?;
-        let tool_config = ToolConfiguration::new(name.clone(), path.to_string_lossy(), settings);
-        let summary = sync::sync_tool(&tool_config, &master, &state.db)?;
+    for record in tools {
+        let summary = sync::sync_registered_tool(&state.db, &record)?;
         summaries.push(summary);
     }
-    Ok(Json(summaries))
+    // 일부 도구만 실패한 경우에도 나머지 결과를 돌려주며, 207 로 부분 성공을 알립니다.
+    let status = if summaries
+        .iter()
+        .any(|summary| summary.status == SyncStatus::Failed)
+    {
+        StatusCode::MULTI_STATUS
+    } else {
+        StatusCode::OK
+    };
+    Ok((status, Json(SyncResponse::Applied(summaries))))
 }
 
 async fn sync_history(State(state): State<AppState>) -> BackendResult<Json<Vec<SyncSummary>>> {
     let history = state.db.recent_sync_history(25)?;
     Ok(Json(history))
 }
+
+async fn audit_secrets(State(state): State<AppState>) -> BackendResult<Json<Vec<SecretFinding>>> {
+    Ok(Json(audit::scan_tools(&state.db)?))
+}
+
+async fn migrate_secrets(State(state): State<AppState>) -> BackendResult<Json<SecretMigration>> {
+    let findings = audit::scan_tools(&state.db)?;
+    let migration = audit::migrate_secrets(
+        &state.db,
+        &findings,
+        &RevisionMeta::new(RevisionSource::Api, None),
+    )?;
+    Ok(Json(migration))
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config::{RevisionMeta, ToolConfiguration, DEFAULT_PROFILE};
+use crate::db::Database;
+use crate::error::BackendResult;
+use crate::redact;
+use crate::secrets;
+use crate::sync;
+
+/// 도구 설정 파일에 평문으로 남아 있는 자격 증명. 원문은 직렬화하지 않습니다.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SecretFinding {
+    pub tool: String,
+    pub config_path: String,
+    pub server: String,
+    /// `api_key`, `env.GITHUB_TOKEN`, `headers.Authorization`, `args[2]` 형태의 위치
+    pub field: String,
+    pub preview: String,
+    #[serde(skip)]
+    pub value: String,
+}
+
+/// `migrate_secrets` 결과
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SecretMigration {
+    /// 새로 저장한 비밀 이름
+    pub stored: Vec<String>,
+    pub migrated: Vec<SecretFinding>,
+    /// 마스터의 평문 값을 참조로 바꿨으면 새 리비전 ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision: Option<i64>,
+    /// 평문 값을 참조로 바꾼 이름 있는 프로필. 이름 있는 프로필은 리비전을 남기지 않습니다.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<String>,
+}
+
+/// 등록된 모든 도구 설정 파일을 검사합니다. 비밀 저장소에 있는 값은 동기화가 기록한 것이므로
+/// 참조로 바뀌어 보고되지 않습니다. 읽을 수 없는 파일은 건너뜁니다.
+pub fn scan_tools(db: &Database) -> BackendResult<Vec<SecretFinding>> {
+    let secrets = db.load_secrets()?;
+    let mut findings = Vec::new();
+    for record in db.list_tools()? {
+        if let Ok(tool) = sync::load_tool(&record, &secrets) {
+            findings.extend(scan_tool(&tool));
+        }
+    }
+    Ok(findings)
+}
+
+/// 자격 증명 이름의 키(`api_key`, `GITHUB_TOKEN`, `Authorization` 등) 값과
+/// `sk-`, `ghp_` 처럼 토큰으로 보이는 값을 찾습니다. `${...}` 참조는 제외합니다.
+pub fn scan_tool(tool: &ToolConfiguration) -> Vec<SecretFinding> {
+    let mut findings = Vec::new();
+    for server in &tool.settings.servers {
+        let Ok(Value::Object(fields)) = serde_json::to_value(server) else {
+            continue;
+        };
+        let mut found = Vec::new();
+        for (key, field) in &fields {
+            if key != "id" {
+                scan_value(key, key, field, &mut found);
+            }
+        }
+        findings.extend(found.into_iter().map(|(field, value)| SecretFinding {
+            tool: tool.name.clone(),
+            config_path: tool.config_path.clone(),
+            server: server.id.clone(),
+            field,
+            preview: redact::mask(&value),
+            value,
+        }));
+    }
+    findings
+}
+
+/// 찾은 값을 비밀 저장소에 넣고, 마스터 구성에 같은 값이 있으면 `${secret:이름}` 참조로 바꿉니다.
+/// 도구 파일은 다음 동기화부터 참조로 비교되어 데이터베이스에도 원문이 남지 않습니다.
+pub fn migrate_secrets(
+    db: &Database,
+    findings: &[SecretFinding],
+    meta: &RevisionMeta,
+) -> BackendResult<SecretMigration> {
+    let existing: Vec<String> = db
+        .list_secrets()?
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect();
+    let mut names: HashMap<&str, String> = HashMap::new();
+    let mut migration = SecretMigration::default();
+    // 같은 값이 여러 곳에 있으면 `env`, 헤더처럼 이름이 드러나는 위치에서 비밀 이름을 짓습니다.
+    let mut ordered: Vec<&SecretFinding> = findings.iter().collect();
+    ordered.sort_by_key(|finding| finding.field.starts_with("args"));
+    for finding in ordered {
+        if !names.contains_key(finding.value.as_str()) {
+            let name = unique_name(&secret_name(finding), |name| {
+                existing.iter().any(|item| item == name) || names.values().any(|item| item == name)
+            });
+            db.set_secret(&name, &finding.value)?;
+            migration.stored.push(name.clone());
+            names.insert(&finding.value, name);
+        }
+    }
+    migration.migrated = findings.to_vec();
+    if migration.stored.is_empty() {
+        return Ok(migration);
+    }
+
+    let secrets = db.load_secrets()?;
+    let meta = meta.clone().with_message(format!(
+        "moved {} plaintext credential(s) into the secret store",
+        migration.stored.len()
+    ));
+    // 기본 프로필(마스터 구성)뿐 아니라 도구가 고를 수 있는 모든 프로필의 같은 값을 바꿉니다.
+    for profile in db.list_profiles()? {
+        let concealed = secrets.conceal(&profile.settings);
+        if concealed == profile.settings {
+            continue;
+        }
+        if profile.name == DEFAULT_PROFILE {
+            migration.revision = Some(db.upsert_master_config(&concealed, &meta)?);
+        } else {
+            db.save_profile(&profile.name, &concealed, &meta)?;
+            migration.profiles.push(profile.name);
+        }
+    }
+    Ok(migration)
+}
+
+/// `github` 서버의 `env.GITHUB_TOKEN` 은 `github.github_token` 이 됩니다.
+fn secret_name(finding: &SecretFinding) -> String {
+    let leaf = finding.field.rsplit('.').next().unwrap_or(&finding.field);
+    let raw = format!("{}.{}", finding.server, leaf).to_ascii_lowercase();
+    let name: String = raw
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_matches('_').to_string();
+    if secrets::validate_name(&name).is_ok() {
+        name
+    } else {
+        "credential".to_string()
+    }
+}
+
+fn unique_name(base: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|suffix| format!("{base}-{suffix}"))
+        .find(|name| !taken(name))
+        .unwrap_or_else(|| base.to_string())
+}
+
+fn scan_value(path: &str, key: &str, value: &Value, found: &mut Vec<(String, String)>) {
+    match value {
+        Value::String(text)
+            if is_plaintext(text)
+                && (redact::is_sensitive_name(key) || redact::looks_like_token(text)) =>
+        {
+            found.push((path.to_string(), text.clone()))
+        }
+        Value::Array(items) if key == "args" => scan_args(path, items, found),
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                scan_value(&format!("{path}[{index}]"), key, item, found);
+            }
+        }
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                scan_value(&format!("{path}.{key}"), key, field, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `--token VALUE`, `--api-key=VALUE` 의 값과 토큰처럼 보이는 인자를 찾습니다.
+fn scan_args(path: &str, items: &[Value], found: &mut Vec<(String, String)>) {
+    let mut flagged = false;
+    for (index, item) in items.iter().enumerate() {
+        let Value::String(arg) = item else {
+            flagged = false;
+            continue;
+        };
+        let position = format!("{path}[{index}]");
+        if flagged && !arg.starts_with('-') {
+            if is_plaintext(arg) {
+                found.push((position, arg.clone()));
+            }
+            flagged = false;
+            continue;
+        }
+        flagged = false;
+        if let Some(flag) = arg
+            .strip_prefix('-')
+            .filter(|flag| redact::is_sensitive_name(flag))
+        {
+            match flag.split_once('=') {
+                Some((_, secret)) if is_plaintext(secret) => {
+                    found.push((position, secret.to_string()))
+                }
+                Some(_) => {}
+                None => flagged = true,
+            }
+        } else if redact::looks_like_token(arg) {
+            found.push((position, arg.clone()));
+        }
+    }
+}
+
+fn is_plaintext(text: &str) -> bool {
+    !text.is_empty() && !redact::is_reference(text)
+}
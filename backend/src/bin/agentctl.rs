@@ -3,17 +3,29 @@ use std::io::{self, Read};
 use std::path::PathBuf;
 
 use anyhow::{anyhow, Context, Result};
+use backend::audit;
 use backend::config::{
-    MasterConfigResponse, McpSettings, RecommendedServer, SyncStatus, SyncSummary,
-    ToolConfiguration,
+    ConflictSide, EnvMode, MasterRevision, McpSettings, RecommendedServer, RevisionMeta,
+    RevisionSource, SyncPlan, SyncPolicy, SyncStatus, SyncSummary, ToolConfiguration,
+    DEFAULT_PROFILE,
 };
-use backend::db::Database;
+use backend::db::{Database, ToolRecord};
+use backend::diff::{self, FieldChange, ProjectOverrideChange, ServerChange, SettingsDiff};
+use backend::redact::{self, Redact};
+use backend::secrets;
+use backend::settings::{Settings, SettingsOverrides};
 use backend::sync;
 use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(name = "agentctl", about = "AI MCP 동기화 CLI", version)]
 struct Cli {
+    /// SQLite 데이터베이스 파일 경로 (기본: $XDG_DATA_HOME/ai-mcp-sync/mcp_sync.db)
+    #[arg(long, global = true, value_name = "PATH")]
+    db: Option<PathBuf>,
+    /// `<DIR>/<도구 이름>/mcp.json` 형태의 도구 설정을 추가로 검색할 디렉터리 (기본: 있으면 ../tool_configs)
+    #[arg(long, global = true, value_name = "DIR")]
+    config_root: Option<PathBuf>,
     #[command(subcommand)]
     command: Command,
 }
@@ -32,21 +44,67 @@ enum Command {
     Rules(RulesCommand),
     /// 추천 MCP 서버를 마스터 구성에 적용하고 지정한 에이전트에 동기화합니다.
     Apply(ApplyArgs),
+    /// 마스터 구성과 도구 구성의 차이를 항목별로 출력합니다.
+    Diff(DiffArgs),
     /// 마스터 구성으로 도구를 동기화합니다.
     Sync(SyncArgs),
     /// 최근 동기화 기록을 확인합니다.
     History(HistoryArgs),
+    /// 동기화 직전에 저장한 스냅샷으로 도구 설정 파일을 되돌립니다.
+    Restore(RestoreArgs),
+    /// 마스터와 도구가 함께 바꾼 서버의 충돌을 한쪽 값으로 해결하고 다시 동기화합니다.
+    Resolve(ResolveArgs),
     /// 에이전트별 기능(현재는 MCP 서버 활성화) 토글
     #[command(subcommand)]
     Feature(FeatureCommand),
+    /// 등록된 도구별 설정을 관리합니다.
+    #[command(subcommand)]
+    Tool(ToolCommand),
+    /// `${secret:이름}` 참조에 쓰는 암호화된 비밀 값을 관리합니다.
+    #[command(subcommand)]
+    Secret(SecretCommand),
+    /// 도구마다 고를 수 있는 이름 있는 마스터 프로필을 관리합니다.
+    #[command(subcommand)]
+    Profile(ProfileCommand),
+    /// 도구 설정 파일을 보안 관점에서 점검합니다.
+    #[command(subcommand)]
+    Audit(AuditCommand),
 }
 
 #[derive(Subcommand)]
 enum MasterCommand {
     /// 현재 마스터 MCP 구성을 출력합니다.
-    Show,
+    Show(RevealArgs),
     /// JSON 파일에서 마스터 MCP 구성을 갱신합니다.
     Set(MasterSetArgs),
+    /// 마스터 구성 리비전 기록을 출력합니다.
+    Log(MasterLogArgs),
+    /// 지정한 리비전의 마스터 구성을 새 리비전으로 되살립니다.
+    Rollback(MasterRollbackArgs),
+}
+
+#[derive(Args)]
+struct RevealArgs {
+    /// api_key, env 등 자격 증명을 가리지 않고 출력합니다. 관리자 토큰이 필요합니다.
+    #[arg(long, action = ArgAction::SetTrue, requires = "admin_token")]
+    reveal: bool,
+    /// API 의 `?reveal=true` 와 같은 관리자 토큰 (MCP_SYNC_ADMIN_TOKEN 또는 설정 파일의 admin_token)
+    #[arg(long, value_name = "TOKEN", requires = "reveal")]
+    admin_token: Option<String>,
+}
+
+#[derive(Args)]
+struct MasterLogArgs {
+    /// 출력할 리비전 개수 (기본: 20)
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+}
+
+#[derive(Args)]
+struct MasterRollbackArgs {
+    /// 되돌릴 리비전 번호
+    #[arg(value_name = "REV")]
+    revision: i64,
 }
 
 #[derive(Args)]
@@ -75,11 +133,28 @@ struct ApplyArgs {
     enabled: Option<bool>,
 }
 
+#[derive(Args)]
+struct DiffArgs {
+    /// 특정 도구만 비교하려면 지정합니다.
+    #[arg(long, value_name = "AGENT")]
+    agent: Option<String>,
+    /// JSON 형식으로 출력합니다.
+    #[arg(long, action = ArgAction::SetTrue)]
+    json: bool,
+    #[command(flatten)]
+    reveal: RevealArgs,
+}
+
 #[derive(Args)]
 struct SyncArgs {
     /// 특정 도구만 동기화하려면 지정합니다.
     #[arg(long, value_name = "AGENT")]
     agent: Option<String>,
+    /// 파일을 수정하지 않고 변경 예정 내용만 출력합니다.
+    #[arg(long, action = ArgAction::SetTrue)]
+    dry_run: bool,
+    #[command(flatten)]
+    reveal: RevealArgs,
 }
 
 #[derive(Args)]
@@ -89,6 +164,185 @@ struct HistoryArgs {
     limit: usize,
 }
 
+#[derive(Args)]
+struct RestoreArgs {
+    /// 복원할 에이전트(도구) 이름
+    #[arg(long, value_name = "AGENT")]
+    agent: String,
+    /// 복원할 동기화 ID (기본: 가장 최근 백업)
+    #[arg(long, value_name = "SYNC_ID")]
+    at: Option<i64>,
+}
+
+#[derive(Subcommand)]
+enum ToolCommand {
+    /// 도구의 동기화 정책을 확인하거나 변경합니다.
+    Policy(ToolPolicyArgs),
+    /// 마스터의 `${env:이름}` 참조를 풀어 기록할지(expand), 그대로 둘지(passthrough) 정합니다.
+    Env(ToolEnvArgs),
+}
+
+#[derive(Args)]
+struct ToolPolicyArgs {
+    /// 에이전트(도구) 이름
+    #[arg(long, value_name = "AGENT")]
+    agent: String,
+    /// 새 정책 (mirror, additive, preserve-local). 생략하면 현재 정책을 출력합니다.
+    #[arg(value_name = "POLICY")]
+    policy: Option<SyncPolicy>,
+}
+
+#[derive(Args)]
+struct ToolEnvArgs {
+    /// 에이전트(도구) 이름
+    #[arg(long, value_name = "AGENT")]
+    agent: String,
+    /// 새 방식 (expand, passthrough). 생략하면 현재 방식을 출력합니다.
+    #[arg(value_name = "MODE")]
+    mode: Option<EnvMode>,
+}
+
+#[derive(Args)]
+struct ResolveArgs {
+    /// 에이전트(도구) 이름
+    #[arg(long, value_name = "AGENT")]
+    agent: String,
+    /// 충돌한 서버 ID
+    #[arg(long, value_name = "SERVER_ID")]
+    server: String,
+    /// 남길 쪽 (master 또는 tool)
+    #[arg(long, value_name = "SIDE")]
+    take: ConflictSide,
+}
+
+#[derive(Subcommand)]
+enum ProfileCommand {
+    /// 새 프로필을 만듭니다. 파일을 지정하지 않으면 기본 프로필을 복사합니다.
+    Create(ProfileCreateArgs),
+    /// 프로필 목록과 각 프로필로 동기화하는 도구를 출력합니다.
+    List,
+    /// 도구가 동기화에 쓸 프로필을 지정합니다.
+    Use(ProfileUseArgs),
+    /// 프로필의 MCP 구성을 출력합니다.
+    Show(ProfileShowArgs),
+    /// JSON 파일에서 프로필 구성을 갱신합니다.
+    Set(ProfileSetArgs),
+    /// 프로필 리비전 기록을 출력합니다.
+    Log(ProfileLogArgs),
+    /// 지정한 리비전의 프로필 구성을 새 리비전으로 되살립니다.
+    Rollback(ProfileRollbackArgs),
+    /// 프로필을 삭제합니다. 사용 중인 도구가 있으면 삭제하지 않습니다.
+    Rm(ProfileNameArgs),
+}
+
+#[derive(Args)]
+struct ProfileCreateArgs {
+    /// 프로필 이름
+    #[arg(value_name = "NAME")]
+    name: String,
+    /// 초기 구성 JSON 파일 경로 ("-" 입력 시 STDIN 사용)
+    #[arg(long, value_name = "PATH")]
+    from: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct ProfileUseArgs {
+    /// 프로필 이름 (기본 마스터 구성은 default)
+    #[arg(value_name = "NAME")]
+    name: String,
+    /// 에이전트(도구) 이름
+    #[arg(long, value_name = "AGENT")]
+    agent: String,
+}
+
+#[derive(Args)]
+struct ProfileShowArgs {
+    /// 프로필 이름
+    #[arg(value_name = "NAME")]
+    name: String,
+    #[command(flatten)]
+    reveal: RevealArgs,
+}
+
+#[derive(Args)]
+struct ProfileSetArgs {
+    /// 프로필 이름
+    #[arg(value_name = "NAME")]
+    name: String,
+    /// JSON 파일 경로 ("-" 입력 시 STDIN 사용)
+    #[arg(value_name = "PATH")]
+    path: PathBuf,
+}
+
+#[derive(Args)]
+struct ProfileLogArgs {
+    /// 프로필 이름
+    #[arg(value_name = "NAME")]
+    name: String,
+    /// 출력할 리비전 개수 (기본: 20)
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+}
+
+#[derive(Args)]
+struct ProfileRollbackArgs {
+    /// 프로필 이름
+    #[arg(value_name = "NAME")]
+    name: String,
+    /// 되돌릴 리비전 번호
+    #[arg(value_name = "REV")]
+    revision: i64,
+}
+
+#[derive(Args)]
+struct ProfileNameArgs {
+    /// 프로필 이름
+    #[arg(value_name = "NAME")]
+    name: String,
+}
+
+#[derive(Subcommand)]
+enum AuditCommand {
+    /// 등록된 도구 설정 파일에서 평문 자격 증명(api_key, 토큰 등)을 찾습니다.
+    Secrets(AuditSecretsArgs),
+}
+
+#[derive(Args)]
+struct AuditSecretsArgs {
+    /// 찾은 값을 비밀 저장소로 옮기고 마스터 구성의 같은 값을 참조로 바꿉니다.
+    #[arg(long, action = ArgAction::SetTrue)]
+    migrate: bool,
+}
+
+#[derive(Subcommand)]
+enum SecretCommand {
+    /// 비밀 값을 저장합니다. 값을 생략하면 STDIN 첫 줄을 사용합니다.
+    Set(SecretSetArgs),
+    /// 저장된 비밀 값을 출력합니다.
+    Get(SecretNameArgs),
+    /// 비밀 값을 삭제합니다.
+    Rm(SecretNameArgs),
+    /// 저장된 비밀 이름과 이를 참조하는 프로필을 출력합니다.
+    List,
+}
+
+#[derive(Args)]
+struct SecretSetArgs {
+    /// 비밀 이름 (영문, 숫자, '_', '-', '.')
+    #[arg(value_name = "NAME")]
+    name: String,
+    /// 비밀 값 (셸 기록에 남지 않도록 생략하고 STDIN 으로 전달하는 것을 권장합니다)
+    #[arg(value_name = "VALUE")]
+    value: Option<String>,
+}
+
+#[derive(Args)]
+struct SecretNameArgs {
+    /// 비밀 이름
+    #[arg(value_name = "NAME")]
+    name: String,
+}
+
 #[derive(Subcommand)]
 enum FeatureCommand {
     /// MCP 서버의 활성화 여부를 토글합니다.
@@ -122,25 +376,61 @@ struct FeatureToggleArgs {
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    let db = Database::initialize()?;
+    let settings = Settings::load(SettingsOverrides {
+        db_path: cli.db,
+        config_root: cli.config_root,
+    })?;
+    if let Some(reveal) = cli.command.reveal_args().filter(|args| args.reveal) {
+        settings.authorize_reveal(reveal.admin_token.as_deref())?;
+    }
+    let db = Database::initialize(&settings.db_path)?;
 
     match cli.command {
-        Command::Scan => handle_scan(&db),
+        Command::Scan => handle_scan(&db, &settings),
         Command::List => handle_list(&db),
         Command::Master(cmd) => handle_master(&db, cmd),
         Command::Rules(cmd) => handle_rules(&db, cmd),
         Command::Apply(args) => handle_apply(&db, args),
+        Command::Diff(args) => handle_diff(&db, args),
         Command::Sync(args) => handle_sync(&db, args),
         Command::History(args) => handle_history(&db, args),
+        Command::Restore(args) => handle_restore(&db, args),
+        Command::Resolve(args) => handle_resolve(&db, args),
         Command::Feature(cmd) => handle_feature(&db, cmd),
+        Command::Tool(cmd) => handle_tool(&db, cmd),
+        Command::Secret(cmd) => handle_secret(&db, cmd),
+        Command::Profile(cmd) => handle_profile(&db, cmd),
+        Command::Audit(cmd) => handle_audit(&db, cmd),
+    }
+}
+
+impl Command {
+    fn reveal_args(&self) -> Option<&RevealArgs> {
+        match self {
+            Command::Master(MasterCommand::Show(args)) => Some(args),
+            Command::Diff(args) => Some(&args.reveal),
+            Command::Sync(args) => Some(&args.reveal),
+            Command::Profile(ProfileCommand::Show(args)) => Some(&args.reveal),
+            _ => None,
+        }
+    }
+}
+
+impl RevealArgs {
+    fn apply<T: Redact>(&self, value: T) -> T {
+        if self.reveal {
+            value
+        } else {
+            value.redacted()
+        }
     }
 }
 
-fn handle_scan(db: &Database) -> Result<()> {
-    let discovered = sync::discover_tools(db)?;
+fn handle_scan(db: &Database, settings: &Settings) -> Result<()> {
+    let discovered = sync::discover_tools(db, settings)?;
     println!("{}개의 도구 구성을 검색했습니다.", discovered.len());
     for tool in discovered {
-        println!("- {} ({})", tool.name, tool.config_path);
+        println!("- {} [{}] ({})", tool.name, tool.kind, tool.config_path);
     }
     Ok(())
 }
@@ -156,17 +446,19 @@ fn handle_list(db: &Database) -> Result<()> {
 
     println!("마스터 구성 최신화: {}", master.updated_at.to_rfc3339());
     println!("\n도구 목록:");
-    for tool in tools {
-        println!("\n■ {}", tool.name);
-        println!("  경로: {}", tool.config_path);
-        if tool.settings == master.settings {
-            println!("  상태: ✅ 마스터와 동기화됨");
-        } else {
-            let diff = describe_diff(&master.settings, &tool.settings);
-            println!("  상태: ⚠️ 동기화 필요");
-            if let Some(diff) = diff {
-                println!("    차이: {}", diff);
+    for (record, tool) in tools {
+        println!("\n■ {}", record.name);
+        println!("  경로: {}", record.config_path.display());
+        println!("  정책: {}", record.policy);
+        println!("  환경 변수: {}", record.env_mode);
+        println!("  프로필: {}", profile_name(record.profile.as_deref()));
+        match tool.and_then(|tool| diff_against_profile(db, &tool)) {
+            Ok(diff) if diff.in_sync => println!("  상태: ✅ 마스터와 동기화됨"),
+            Ok(diff) => {
+                println!("  상태: ⚠️ 동기화 필요");
+                println!("    차이: {}", summarize_diff(&diff.changes));
             }
+            Err(err) => println!("  상태: ❌ 설정을 읽지 못했습니다: {}", err),
         }
     }
     Ok(())
@@ -174,20 +466,38 @@ fn handle_list(db: &Database) -> Result<()> {
 
 fn handle_master(db: &Database, cmd: MasterCommand) -> Result<()> {
     match cmd {
-        MasterCommand::Show => {
-            let config = db.ensure_master_config()?;
+        MasterCommand::Show(args) => {
+            let config = args.apply(db.ensure_master_config()?);
             let rendered = serde_json::to_string_pretty(&config.settings)?;
             println!("{}", rendered);
             Ok(())
         }
         MasterCommand::Set(args) => {
-            let content = read_from_path_or_stdin(&args.path)?;
-            let parsed: McpSettings = serde_json::from_str(&content)
-                .context("JSON 형식의 MCP 설정을 읽는 데 실패했습니다")?;
-            db.upsert_master_config(&parsed)?;
+            let mut parsed = read_settings(&args.path)?;
+            // 'master show' 출력을 고쳐 다시 넣는 경우 가려진 값은 저장된 원래 값을 유지합니다.
+            redact::restore_masked(&mut parsed, &db.ensure_master_config()?.settings);
+            let revision = db.upsert_master_config(&parsed, &cli_revision())?;
             let updated = db.ensure_master_config()?;
             println!(
-                "마스터 구성을 갱신했습니다. ({} 서버)",
+                "마스터 구성을 갱신했습니다. (리비전 #{}, {} 서버)",
+                revision,
+                updated.settings.servers.len()
+            );
+            warn_about_secrets(db, &updated.settings)
+        }
+        MasterCommand::Log(args) => {
+            let revisions = db.list_master_revisions(args.limit)?;
+            if revisions.is_empty() {
+                println!("마스터 구성 리비전이 없습니다.");
+            }
+            revisions.iter().for_each(print_revision);
+            Ok(())
+        }
+        MasterCommand::Rollback(args) => {
+            let updated = db.rollback_master_config(args.revision, cli_revision())?;
+            println!(
+                "마스터 구성을 리비전 #{} 로 되돌렸습니다. ({} 서버)",
+                args.revision,
                 updated.settings.servers.len()
             );
             Ok(())
@@ -195,6 +505,30 @@ fn handle_master(db: &Database, cmd: MasterCommand) -> Result<()> {
     }
 }
 
+fn print_revision(revision: &MasterRevision) {
+    println!(
+        "- #{} [{}] {}{} :: {} 서버{}",
+        revision.id,
+        revision.created_at.to_rfc3339(),
+        revision.source.as_str(),
+        revision
+            .author
+            .as_deref()
+            .map(|author| format!(" ({})", author))
+            .unwrap_or_default(),
+        revision.settings.servers.len(),
+        revision
+            .message
+            .as_deref()
+            .map(|message| format!(" - {}", message))
+            .unwrap_or_default()
+    );
+}
+
+fn cli_revision() -> RevisionMeta {
+    RevisionMeta::new(RevisionSource::Cli, std::env::var("USER").ok())
+}
+
 fn handle_rules(db: &Database, cmd: RulesCommand) -> Result<()> {
     match cmd {
         RulesCommand::List => {
@@ -217,25 +551,99 @@ fn handle_apply(db: &Database, args: ApplyArgs) -> Result<()> {
         .get_recommended_server(&args.rule)?
         .ok_or_else(|| anyhow!("추천 서버 '{}'를 찾을 수 없습니다.", args.rule))?;
 
-    let mut master = db.ensure_master_config()?.settings;
+    // 에이전트가 쓰는 프로필에 적용합니다.
+    let record = find_agent(db, &args.agent)?;
+    let profile = profile_name(record.profile.as_deref());
+    let mut master = db.profile_settings(Some(profile))?;
     let enabled = args.enabled.unwrap_or(server.default_enabled);
-    master.apply_recommended_server(&server, enabled);
-    db.upsert_master_config(&master)?;
-    println!("'{}' 서버를 마스터 구성에 적용했습니다.", server.name);
+    master.apply_recommended_server(&server, enabled)?;
+    db.save_profile(
+        profile,
+        &master,
+        &RevisionMeta::new(RevisionSource::Import, std::env::var("USER").ok())
+            .with_message(format!("imported recommended server '{}'", server.id)),
+    )?;
+    println!(
+        "'{}' 서버를 '{}' 프로필에 적용했습니다.",
+        server.name, profile
+    );
 
-    let refreshed = db.ensure_master_config()?;
-    let summary = sync_tool_for_agent(db, &args.agent, &refreshed)?;
+    let summary = sync::sync_registered_tool(db, &record)?;
     print_sync_summary(&summary, true);
     Ok(())
 }
 
+fn handle_diff(db: &Database, args: DiffArgs) -> Result<()> {
+    let mut tools = load_tool_configs(db)?;
+    if let Some(ref target) = args.agent {
+        tools.retain(|(record, _)| &record.name == target);
+        if tools.is_empty() {
+            return Err(anyhow!("'{}' 이름의 도구를 찾을 수 없습니다.", target));
+        }
+    }
+
+    let results: Vec<_> = tools
+        .into_iter()
+        .map(|(record, tool)| {
+            let diff = tool.and_then(|tool| diff_against_profile(db, &tool));
+            (record, diff.map(|diff| args.reveal.apply(diff)))
+        })
+        .collect();
+    let failed = results.iter().filter(|(_, diff)| diff.is_err()).count();
+
+    if args.json {
+        let diffs: Vec<_> = results
+            .iter()
+            .filter_map(|(_, diff)| diff.as_ref().ok())
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&diffs)?);
+        for (record, diff) in &results {
+            if let Err(err) = diff {
+                eprintln!("⚠️  {} 의 설정을 읽지 못했습니다: {}", record.name, err);
+            }
+        }
+    } else {
+        if results.is_empty() {
+            println!("등록된 도구가 없습니다. 먼저 'agentctl scan'을 실행하세요.");
+        }
+        for (record, diff) in results {
+            println!("\n■ {} ({})", record.name, record.config_path.display());
+            match diff {
+                Ok(diff) => {
+                    if diff.in_sync {
+                        println!("  ✅ 마스터와 동기화됨");
+                    } else {
+                        print_changes(&diff.changes);
+                    }
+                    print_warnings(&diff.warnings);
+                }
+                Err(err) => println!("  ❌ 설정을 읽지 못했습니다: {}", err),
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow!("{}개 도구의 설정을 비교하지 못했습니다.", failed));
+    }
+    Ok(())
+}
+
+fn diff_against_profile(db: &Database, tool: &ToolConfiguration) -> Result<diff::ToolDiff> {
+    let master = db.profile_settings(tool.profile.as_deref())?;
+    Ok(diff::diff_tool(tool, &master)?)
+}
+
 fn handle_sync(db: &Database, args: SyncArgs) -> Result<()> {
-    let SyncArgs { agent } = args;
-    let master = db.ensure_master_config()?;
+    let SyncArgs {
+        agent,
+        dry_run,
+        reveal,
+    } = args;
     let mut entries = db.list_tools()?;
 
     if let Some(ref target) = agent {
-        entries.retain(|(name, _)| name == target);
+        // 에이전트 이름을 주면 그 에이전트의 프로젝트 단위 설정도 함께 동기화합니다.
+        entries.retain(|record| record.name == *target || record.agent == *target);
     }
 
     if entries.is_empty() {
@@ -247,12 +655,32 @@ fn handle_sync(db: &Database, args: SyncArgs) -> Result<()> {
         return Ok(());
     }
 
-    for (name, path) in entries {
-        let config = build_tool_configuration(name, path)?;
-        let summary = sync::sync_tool(&config, &master.settings, db)?;
+    let mut failed = 0;
+    for record in entries {
+        if dry_run {
+            let plan = sync::plan_registered_tool(db, &record)?;
+            if plan.error.is_some() {
+                failed += 1;
+            }
+            print_sync_plan(&reveal.apply(plan));
+            continue;
+        }
+        let summary = sync::sync_registered_tool(db, &record)?;
+        if summary.status == SyncStatus::Failed {
+            failed += 1;
+        }
         print_sync_summary(&summary, false);
     }
 
+    if failed > 0 && dry_run {
+        return Err(anyhow!(
+            "{}개 도구의 동기화 계획을 세우지 못했습니다.",
+            failed
+        ));
+    }
+    if failed > 0 {
+        return Err(anyhow!("{}개 도구의 동기화에 실패했습니다.", failed));
+    }
     Ok(())
 }
 
@@ -264,7 +692,8 @@ fn handle_history(db: &Database, args: HistoryArgs) -> Result<()> {
         println!("최근 동기화 기록:");
         for item in history {
             println!(
-                "- [{}] {} :: {}",
+                "- #{} [{}] {} :: {}",
+                item.id.unwrap_or_default(),
                 item.synced_at.to_rfc3339(),
                 item.tool,
                 format_status(&item)
@@ -274,6 +703,21 @@ fn handle_history(db: &Database, args: HistoryArgs) -> Result<()> {
     Ok(())
 }
 
+fn handle_restore(db: &Database, args: RestoreArgs) -> Result<()> {
+    let summary = sync::restore_tool(db, &args.agent, args.at)?;
+    print_sync_summary(&summary, true);
+    Ok(())
+}
+
+fn handle_resolve(db: &Database, args: ResolveArgs) -> Result<()> {
+    let record = db
+        .find_tool(&args.agent)?
+        .ok_or_else(|| anyhow!("'{}' 이름의 도구를 찾을 수 없습니다.", args.agent))?;
+    let summary = sync::resolve_conflict(db, &record, &args.server, args.take)?;
+    print_sync_summary(&summary, true);
+    Ok(())
+}
+
 fn handle_feature(db: &Database, cmd: FeatureCommand) -> Result<()> {
     match cmd {
         FeatureCommand::Toggle(args) => handle_feature_toggle(db, args),
@@ -296,14 +740,30 @@ fn handle_feature_toggle(db: &Database, args: FeatureToggleArgs) -> Result<()> {
         }
     };
 
-    let mut master = db.ensure_master_config()?.settings;
+    let record = find_agent(db, &args.agent)?;
+    let profile = profile_name(record.profile.as_deref());
+    let mut master = db.profile_settings(Some(profile))?;
     let server = master
         .servers
         .iter_mut()
         .find(|item| item.id == args.key)
-        .ok_or_else(|| anyhow!("마스터 구성에서 '{}' 서버를 찾을 수 없습니다.", args.key))?;
+        .ok_or_else(|| {
+            anyhow!(
+                "'{}' 프로필에서 '{}' 서버를 찾을 수 없습니다.",
+                profile,
+                args.key
+            )
+        })?;
     server.enabled = desired;
-    db.upsert_master_config(&master)?;
+    db.save_profile(
+        profile,
+        &master,
+        &cli_revision().with_message(format!(
+            "{} server '{}'",
+            if desired { "enable" } else { "disable" },
+            args.key
+        )),
+    )?;
 
     println!(
         "'{}' 서버를 {}했습니다.",
@@ -311,32 +771,291 @@ fn handle_feature_toggle(db: &Database, args: FeatureToggleArgs) -> Result<()> {
         if desired { "활성화" } else { "비활성화" }
     );
 
-    let refreshed = db.ensure_master_config()?;
-    let summary = sync_tool_for_agent(db, &args.agent, &refreshed)?;
+    let summary = sync::sync_registered_tool(db, &record)?;
     print_sync_summary(&summary, true);
     Ok(())
 }
 
-fn load_tool_configs(db: &Database) -> Result<Vec<ToolConfiguration>> {
-    let mut tools = Vec::new();
-    for (name, path) in db.list_tools()? {
-        match build_tool_configuration(name.clone(), path) {
-            Ok(tool) => tools.push(tool),
-            Err(err) => {
-                eprintln!("⚠️  {} 의 설정을 불러오는 데 실패했습니다: {}", name, err);
+fn handle_tool(db: &Database, cmd: ToolCommand) -> Result<()> {
+    match cmd {
+        ToolCommand::Policy(args) => handle_tool_policy(db, args),
+        ToolCommand::Env(args) => handle_tool_env(db, args),
+    }
+}
+
+fn handle_tool_env(db: &Database, args: ToolEnvArgs) -> Result<()> {
+    let record = db
+        .find_tool(&args.agent)?
+        .ok_or_else(|| anyhow!("'{}' 이름의 도구를 찾을 수 없습니다.", args.agent))?;
+    match args.mode {
+        Some(mode) => {
+            db.set_tool_env_mode(&record.name, mode)?;
+            println!(
+                "'{}' 의 환경 변수 처리 방식을 {} 로 변경했습니다.",
+                record.name, mode
+            );
+        }
+        None => println!("{}: {}", record.name, record.env_mode),
+    }
+    Ok(())
+}
+
+fn handle_tool_policy(db: &Database, args: ToolPolicyArgs) -> Result<()> {
+    let record = db
+        .find_tool(&args.agent)?
+        .ok_or_else(|| anyhow!("'{}' 이름의 도구를 찾을 수 없습니다.", args.agent))?;
+    match args.policy {
+        Some(policy) => {
+            db.set_tool_policy(&record.name, policy)?;
+            println!(
+                "'{}' 의 동기화 정책을 {} 로 변경했습니다.",
+                record.name, policy
+            );
+        }
+        None => println!("{}: {}", record.name, record.policy),
+    }
+    Ok(())
+}
+
+fn handle_secret(db: &Database, cmd: SecretCommand) -> Result<()> {
+    match cmd {
+        SecretCommand::Set(args) => {
+            let value = match args.value {
+                Some(value) => value,
+                None => {
+                    let mut line = String::new();
+                    io::stdin()
+                        .read_line(&mut line)
+                        .context("STDIN 입력을 읽는데 실패했습니다")?;
+                    line.trim_end_matches(['\r', '\n']).to_string()
+                }
+            };
+            db.set_secret(&args.name, &value)?;
+            println!(
+                "'{}' 비밀을 저장했습니다. 마스터 구성에서 {} 로 참조하세요.",
+                args.name,
+                secrets::reference(&args.name)
+            );
+        }
+        SecretCommand::Get(args) => {
+            let value = db
+                .get_secret(&args.name)?
+                .ok_or_else(|| anyhow!("'{}' 비밀이 없습니다.", args.name))?;
+            println!("{}", value);
+        }
+        SecretCommand::Rm(args) => {
+            db.remove_secret(&args.name)?;
+            println!("'{}' 비밀을 삭제했습니다.", args.name);
+        }
+        SecretCommand::List => {
+            let entries = db.list_secrets()?;
+            if entries.is_empty() {
+                println!("저장된 비밀이 없습니다.");
+                return Ok(());
+            }
+            let referenced: Vec<_> = db
+                .list_profiles()?
+                .into_iter()
+                .map(|profile| (secrets::referenced_names(&profile.settings), profile.name))
+                .collect();
+            for entry in entries {
+                let users: Vec<_> = referenced
+                    .iter()
+                    .filter(|(names, _)| names.contains(&entry.name))
+                    .map(|(_, profile)| profile.as_str())
+                    .collect();
+                let used = if users.is_empty() {
+                    String::new()
+                } else {
+                    format!(" (사용 중인 프로필: {})", users.join(", "))
+                };
+                println!(
+                    "- {} [{}]{}",
+                    entry.name,
+                    entry.updated_at.to_rfc3339(),
+                    used
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_profile(db: &Database, cmd: ProfileCommand) -> Result<()> {
+    match cmd {
+        ProfileCommand::Create(args) => {
+            let settings = match &args.from {
+                Some(path) => read_settings(path)?,
+                None => db.ensure_master_config()?.settings,
+            };
+            db.create_profile(&args.name, &settings)?;
+            println!(
+                "'{}' 프로필을 만들었습니다. ({} 서버)",
+                args.name,
+                settings.servers.len()
+            );
+            warn_about_secrets(db, &settings)?;
+        }
+        ProfileCommand::List => {
+            for profile in db.list_profiles()? {
+                let tools = if profile.tools.is_empty() {
+                    "-".to_string()
+                } else {
+                    profile.tools.join(", ")
+                };
+                println!(
+                    "- {} [{}] {} 서버 / 도구: {}",
+                    profile.name,
+                    profile.updated_at.to_rfc3339(),
+                    profile.settings.servers.len(),
+                    tools
+                );
             }
         }
+        ProfileCommand::Use(args) => {
+            let record = find_agent(db, &args.agent)?;
+            db.set_tool_profile(&record.name, &args.name)?;
+            println!(
+                "'{}' 이 '{}' 프로필로 동기화합니다. 'agentctl sync --agent {}' 로 적용하세요.",
+                record.name, args.name, record.name
+            );
+        }
+        ProfileCommand::Show(args) => {
+            let profile = db
+                .get_profile(&args.name)?
+                .ok_or_else(|| anyhow!("'{}' 프로필이 없습니다.", args.name))?;
+            let profile = args.reveal.apply(profile);
+            println!("{}", serde_json::to_string_pretty(&profile.settings)?);
+        }
+        ProfileCommand::Set(args) => {
+            let mut settings = read_settings(&args.path)?;
+            redact::restore_masked(&mut settings, &db.profile_settings(Some(&args.name))?);
+            db.save_profile(&args.name, &settings, &cli_revision())?;
+            println!(
+                "'{}' 프로필을 갱신했습니다. ({} 서버)",
+                args.name,
+                settings.servers.len()
+            );
+            warn_about_secrets(db, &settings)?;
+        }
+        ProfileCommand::Log(args) => {
+            let revisions = db.list_profile_revisions(&args.name, args.limit)?;
+            if revisions.is_empty() {
+                println!("'{}' 프로필의 리비전이 없습니다.", args.name);
+            }
+            revisions.iter().for_each(print_revision);
+        }
+        ProfileCommand::Rollback(args) => {
+            let settings = db.rollback_profile(&args.name, args.revision, cli_revision())?;
+            println!(
+                "'{}' 프로필을 리비전 #{} 로 되돌렸습니다. ({} 서버)",
+                args.name,
+                args.revision,
+                settings.servers.len()
+            );
+        }
+        ProfileCommand::Rm(args) => {
+            db.remove_profile(&args.name)?;
+            println!("'{}' 프로필을 삭제했습니다.", args.name);
+        }
     }
-    Ok(tools)
+    Ok(())
 }
 
-fn build_tool_configuration(name: String, path: PathBuf) -> Result<ToolConfiguration> {
-    let settings = sync::read_settings_from_file(&path)?;
-    Ok(ToolConfiguration::new(
-        name,
-        path.to_string_lossy(),
-        settings,
-    ))
+fn read_settings(path: &PathBuf) -> Result<McpSettings> {
+    let content = read_from_path_or_stdin(path)?;
+    serde_json::from_str(&content).context("JSON 형식의 MCP 설정을 읽는 데 실패했습니다")
+}
+
+fn handle_audit(db: &Database, cmd: AuditCommand) -> Result<()> {
+    match cmd {
+        AuditCommand::Secrets(args) => {
+            let findings = audit::scan_tools(db)?;
+            if findings.is_empty() {
+                println!("도구 설정 파일에서 평문 자격 증명을 찾지 못했습니다.");
+                return Ok(());
+            }
+            println!("{}개의 평문 자격 증명을 찾았습니다.", findings.len());
+            for finding in &findings {
+                println!(
+                    "- {} :: {} {} = {} ({})",
+                    finding.tool,
+                    finding.server,
+                    finding.field,
+                    finding.preview,
+                    finding.config_path
+                );
+            }
+            if !args.migrate {
+                println!("\n'agentctl audit secrets --migrate' 로 비밀 저장소에 옮길 수 있습니다.");
+                return Ok(());
+            }
+
+            let migration = audit::migrate_secrets(db, &findings, &cli_revision())?;
+            println!();
+            for name in &migration.stored {
+                println!(
+                    "'{}' 비밀을 저장했습니다. ({})",
+                    name,
+                    secrets::reference(name)
+                );
+            }
+            if let Some(revision) = migration.revision {
+                println!(
+                    "마스터 구성의 평문 값을 참조로 바꿨습니다. (리비전 #{})",
+                    revision
+                );
+            }
+            for profile in &migration.profiles {
+                println!("'{}' 프로필의 평문 값을 참조로 바꿨습니다.", profile);
+            }
+            println!("다음 동기화부터 도구 설정도 참조로 비교합니다.");
+        }
+    }
+    Ok(())
+}
+
+/// 마스터 구성이 저장되지 않은 비밀을 참조하거나 평문 `api_key` 를 담고 있으면 알립니다.
+fn warn_about_secrets(db: &Database, settings: &McpSettings) -> Result<()> {
+    let stored: Vec<String> = db
+        .list_secrets()?
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect();
+    for name in secrets::referenced_names(settings) {
+        if !stored.contains(&name) {
+            println!(
+                "⚠️  '{}' 비밀이 저장되어 있지 않습니다. 'agentctl secret set {}' 로 추가하세요.",
+                name, name
+            );
+        }
+    }
+    for server in &settings.servers {
+        if server
+            .api_key
+            .as_deref()
+            .is_some_and(|key| !key.is_empty() && !key.contains("${secret:"))
+        {
+            println!(
+                "⚠️  '{}' 서버의 api_key 가 평문입니다. 비밀 저장소에 넣고 ${{secret:이름}} 으로 참조하세요.",
+                server.id
+            );
+        }
+    }
+    Ok(())
+}
+
+/// 등록된 도구마다 설정을 읽은 결과. 읽지 못한 도구도 목록에 남겨 호출 측이 함께 보고합니다.
+fn load_tool_configs(db: &Database) -> Result<Vec<(ToolRecord, Result<ToolConfiguration>)>> {
+    let secrets = db.load_secrets()?;
+    Ok(db
+        .list_tools()?
+        .into_iter()
+        .map(|record| {
+            let tool = sync::load_tool(&record, &secrets).map_err(Into::into);
+            (record, tool)
+        })
+        .collect())
 }
 
 fn read_from_path_or_stdin(path: &PathBuf) -> Result<String> {
@@ -379,52 +1098,120 @@ fn print_recommended(rule: &RecommendedServer) {
     );
 }
 
-fn sync_tool_for_agent(
-    db: &Database,
-    agent: &str,
-    master: &MasterConfigResponse,
-) -> Result<SyncSummary> {
-    let (name, path) = db
-        .list_tools()?
-        .into_iter()
-        .find(|(name, _)| name == agent)
-        .ok_or_else(|| anyhow!("'{}' 이름의 도구를 찾을 수 없습니다.", agent))?;
-
-    let config = build_tool_configuration(name, path)?;
-    let summary = sync::sync_tool(&config, &master.settings, db)?;
-    Ok(summary)
+fn find_agent(db: &Database, agent: &str) -> Result<ToolRecord> {
+    db.find_tool(agent)?
+        .ok_or_else(|| anyhow!("'{}' 이름의 도구를 찾을 수 없습니다.", agent))
 }
 
-fn describe_diff(master: &McpSettings, tool: &McpSettings) -> Option<String> {
-    if master == tool {
-        return None;
-    }
-    let master_ids: Vec<_> = master.servers.iter().map(|s| s.id.clone()).collect();
-    let tool_ids: Vec<_> = tool.servers.iter().map(|s| s.id.clone()).collect();
+fn profile_name(profile: Option<&str>) -> &str {
+    profile.unwrap_or(DEFAULT_PROFILE)
+}
 
-    let missing: Vec<_> = master_ids
-        .iter()
-        .filter(|id| !tool_ids.contains(id))
-        .cloned()
-        .collect();
-    let extra: Vec<_> = tool_ids
-        .iter()
-        .filter(|id| !master_ids.contains(id))
-        .cloned()
-        .collect();
+fn summarize_diff(changes: &SettingsDiff) -> String {
+    let ids = |predicate: fn(&ServerChange) -> bool| {
+        changes
+            .servers
+            .iter()
+            .filter(|change| predicate(change))
+            .map(ServerChange::id)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let missing = ids(|change| matches!(change, ServerChange::Added { .. }));
+    let extra = ids(|change| matches!(change, ServerChange::Removed { .. }));
+    let modified = ids(|change| matches!(change, ServerChange::Modified { .. }));
 
     let mut parts = Vec::new();
     if !missing.is_empty() {
-        parts.push(format!("{} 추가 필요", missing.join(", ")));
+        parts.push(format!("{} 추가 필요", missing));
     }
     if !extra.is_empty() {
-        parts.push(format!("{} 제거 확인", extra.join(", ")));
+        parts.push(format!("{} 제거 확인", extra));
+    }
+    if !modified.is_empty() {
+        parts.push(format!("{} 변경", modified));
+    }
+    if !changes.project_overrides.is_empty() {
+        parts.push(format!(
+            "프로젝트 오버라이드 {}건 차이",
+            changes.project_overrides.len()
+        ));
     }
+    parts.join(" / ")
+}
 
-    if parts.is_empty() {
-        Some("서버 구성 차이 존재".to_string())
-    } else {
-        Some(parts.join(" / "))
+fn print_changes(changes: &SettingsDiff) {
+    for change in &changes.servers {
+        match change {
+            ServerChange::Added { id, .. } => println!("  + {} (도구에 없음)", id),
+            ServerChange::Removed { id, .. } => println!("  - {} (마스터에 없음)", id),
+            ServerChange::Modified { id, fields } => {
+                println!("  ~ {}", id);
+                print_fields(fields);
+            }
+        }
+    }
+    for change in &changes.project_overrides {
+        match change {
+            ProjectOverrideChange::Added { project, .. } => {
+                println!("  + 프로젝트 {} (도구에 없음)", project)
+            }
+            ProjectOverrideChange::Removed { project, .. } => {
+                println!("  - 프로젝트 {} (마스터에 없음)", project)
+            }
+            ProjectOverrideChange::Modified { project, fields } => {
+                println!("  ~ 프로젝트 {}", project);
+                print_fields(fields);
+            }
+        }
+    }
+}
+
+fn print_fields(fields: &[FieldChange]) {
+    for field in fields {
+        println!("      {}: {} → {}", field.field, field.tool, field.master);
+    }
+}
+
+fn print_sync_plan(plan: &SyncPlan) {
+    if let Some(error) = &plan.error {
+        println!("{} :: ❌ 계획 실패 (드라이런): {}", plan.tool, error);
+        return;
+    }
+    if !plan.changed {
+        println!("{} :: 변경 없음 (드라이런)", plan.tool);
+        print_warnings(&plan.warnings);
+        print_conflicts(plan);
+        return;
+    }
+    println!("{} :: 변경 예정 (드라이런)", plan.tool);
+    print_changes(&plan.changes);
+    print_warnings(&plan.warnings);
+    print_conflicts(plan);
+    println!("  {} 에 기록될 내용:", plan.config_path);
+    for line in plan.proposed.lines() {
+        println!("    {}", line);
+    }
+}
+
+fn print_warnings(warnings: &[String]) {
+    for warning in warnings {
+        println!("  ⚠️  {}", warning);
+    }
+}
+
+fn print_conflicts(plan: &SyncPlan) {
+    for conflict in &plan.conflicts {
+        println!(
+            "  ⛔ 충돌 {}: 마스터 {} / 도구 {} (도구 값 유지)",
+            conflict, conflict.master, conflict.tool
+        );
+    }
+    if !plan.conflicts.is_empty() {
+        println!(
+            "  해결: agentctl resolve --agent {} --server <ID> --take master|tool",
+            plan.tool
+        );
     }
 }
 
@@ -451,5 +1238,6 @@ fn status_label(status: &SyncStatus) -> &'static str {
         SyncStatus::Updated => "업데이트",
         SyncStatus::Skipped => "동일",
         SyncStatus::Failed => "실패",
+        SyncStatus::Conflict => "충돌",
     }
 }
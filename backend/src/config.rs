@@ -1,17 +1,43 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
 use serde_with::{serde_as, DisplayFromStr};
 
+use crate::adapter::AgentKind;
+use crate::diff::SettingsDiff;
+use crate::error::{BackendError, BackendResult};
+
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(remote = "Self")]
 pub struct McpServer {
     pub id: String,
     pub name: String,
     pub description: Option<String>,
+    #[serde(default)]
+    pub transport: McpTransport,
+    /// `streamable_http`/`sse` 전송에서 사용하는 서버 URL
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub endpoint: String,
+    /// `stdio` 전송에서 실행할 명령
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
     pub api_key: Option<String>,
     #[serde(default)]
     pub enabled: bool,
+    /// 이 서비스가 해석하지 않는 에이전트 고유 키 (`alwaysAllow`, `timeout` 등)
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl Default for McpServer {
@@ -20,9 +46,81 @@ impl Default for McpServer {
             id: "default".to_string(),
             name: "Default MCP Server".to_string(),
             description: Some("Fallback local MCP server".to_string()),
+            transport: McpTransport::StreamableHttp,
             endpoint: "http://localhost:3001".to_string(),
+            command: None,
+            args: Vec::new(),
+            env: BTreeMap::new(),
+            cwd: None,
             api_key: None,
             enabled: true,
+            extra: Map::new(),
+        }
+    }
+}
+
+impl McpServer {
+    pub fn is_remote(&self) -> bool {
+        self.transport != McpTransport::Stdio
+    }
+}
+
+impl Serialize for McpServer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        McpServer::serialize(self, serializer)
+    }
+}
+
+/// `transport` 가 없는 항목은 `command` 가 있으면 stdio 로 봅니다.
+/// 실행할 명령도 URL 도 없는 항목은 연결할 수 없으므로 거부합니다.
+impl<'de> Deserialize<'de> for McpServer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut fields = Map::<String, Value>::deserialize(deserializer)?;
+        let has_command = fields.get("command").is_some_and(|value| !value.is_null());
+        if has_command && !fields.contains_key("transport") {
+            fields.insert("transport".to_string(), Value::from("stdio"));
+        }
+        let server = McpServer::deserialize(Value::Object(fields)).map_err(de::Error::custom)?;
+        let connectable = if server.is_remote() {
+            !server.endpoint.is_empty()
+        } else {
+            server
+                .command
+                .as_deref()
+                .is_some_and(|command| !command.is_empty())
+        };
+        if !connectable {
+            let needed = if server.is_remote() {
+                "endpoint"
+            } else {
+                "command"
+            };
+            return Err(de::Error::custom(format!(
+                "server '{}' uses {} transport but has no '{needed}'",
+                server.id,
+                server.transport.as_str()
+            )));
+        }
+        Ok(server)
+    }
+}
+
+/// MCP 서버 연결 방식. 이전 버전의 설정은 `endpoint` 만 가지므로 기본값은 `streamable_http` 입니다.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum McpTransport {
+    Stdio,
+    #[default]
+    StreamableHttp,
+    Sse,
+}
+
+impl McpTransport {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            McpTransport::Stdio => "stdio",
+            McpTransport::StreamableHttp => "streamable_http",
+            McpTransport::Sse => "sse",
         }
     }
 }
@@ -33,16 +131,62 @@ pub struct McpSettings {
     pub servers: Vec<McpServer>,
     #[serde(default)]
     pub project_overrides: Vec<ProjectOverride>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl McpSettings {
-    pub fn apply_recommended_server(&mut self, server: &RecommendedServer, enabled: bool) {
-        if let Some(existing) = self.servers.iter_mut().find(|item| item.id == server.id) {
-            let existing_api_key = existing.api_key.clone();
-            *existing = server.to_mcp_server(enabled);
-            existing.api_key = existing_api_key;
-        } else {
-            self.servers.push(server.to_mcp_server(enabled));
+    /// 같은 id 의 서버가 이미 있으면 비어 있는 필드만 채우고 활성 여부를 갱신합니다.
+    /// 기존 서버의 전송 방식이 추천 서버와 다르면 덮어쓰지 않고 `InvalidConfig` 를 반환합니다.
+    pub fn apply_recommended_server(
+        &mut self,
+        server: &RecommendedServer,
+        enabled: bool,
+    ) -> BackendResult<()> {
+        let recommended = server.to_mcp_server(enabled);
+        let Some(existing) = self.servers.iter_mut().find(|item| item.id == server.id) else {
+            self.servers.push(recommended);
+            return Ok(());
+        };
+        if existing.transport != recommended.transport {
+            return Err(BackendError::InvalidConfig(format!(
+                "server '{}' already uses the {} transport; remove it before importing the {} recommendation",
+                existing.id,
+                existing.transport.as_str(),
+                recommended.transport.as_str()
+            )));
+        }
+        if existing.name.is_empty() {
+            existing.name = recommended.name;
+        }
+        if existing.description.is_none() {
+            existing.description = recommended.description;
+        }
+        if existing.endpoint.is_empty() {
+            existing.endpoint = recommended.endpoint;
+        }
+        existing.enabled = enabled;
+        Ok(())
+    }
+
+    /// 프로젝트 단위 설정 파일에 기록할 마스터 구성. 해당 프로젝트의 `project_overrides`
+    /// 항목이 가리키는 서버만 남깁니다.
+    pub fn for_project(&self, project: &str) -> McpSettings {
+        let server_ids: Vec<&str> = self
+            .project_overrides
+            .iter()
+            .filter(|entry| entry.project == project)
+            .map(|entry| entry.server_id.as_str())
+            .collect();
+        McpSettings {
+            servers: self
+                .servers
+                .iter()
+                .filter(|server| server_ids.contains(&server.id.as_str()))
+                .cloned()
+                .collect(),
+            project_overrides: Vec::new(),
+            extra: self.extra.clone(),
         }
     }
 }
@@ -67,9 +211,15 @@ impl RecommendedServer {
             id: self.id.clone(),
             name: self.name.clone(),
             description: self.description.clone(),
+            transport: McpTransport::StreamableHttp,
             endpoint: self.endpoint.clone(),
+            command: None,
+            args: Vec::new(),
+            env: BTreeMap::new(),
+            cwd: None,
             api_key: None,
             enabled,
+            extra: Map::new(),
         }
     }
 }
@@ -81,26 +231,141 @@ pub struct ProjectOverride {
     pub server_id: String,
     #[serde_as(as = "Option<DisplayFromStr>")]
     pub last_synced_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// 도구별 동기화 정책
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncPolicy {
+    /// 도구 구성을 마스터와 똑같이 맞춥니다. 도구에만 있는 서버는 제거됩니다.
+    Mirror,
+    /// 마스터 서버가 우선하고, 도구에만 있는 서버는 유지합니다.
+    #[default]
+    Additive,
+    /// 도구에 없는 마스터 서버만 추가하고, 도구의 기존 항목은 수정하지 않습니다.
+    PreserveLocal,
+}
+
+impl SyncPolicy {
+    pub const ALL: &'static [SyncPolicy] = &[
+        SyncPolicy::Mirror,
+        SyncPolicy::Additive,
+        SyncPolicy::PreserveLocal,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SyncPolicy::Mirror => "mirror",
+            SyncPolicy::Additive => "additive",
+            SyncPolicy::PreserveLocal => "preserve_local",
+        }
+    }
+}
+
+impl fmt::Display for SyncPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SyncPolicy {
+    type Err = BackendError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let normalized = value.replace('-', "_");
+        SyncPolicy::ALL
+            .iter()
+            .copied()
+            .find(|policy| policy.as_str() == normalized)
+            .ok_or_else(|| {
+                BackendError::Other(format!(
+                    "unknown sync policy '{value}' (expected mirror, additive or preserve-local)"
+                ))
+            })
+    }
+}
+
+/// 마스터의 `${env:이름}` 참조를 도구에 기록하는 방식
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvMode {
+    /// 동기화할 때 환경 변수 값으로 바꿔 기록합니다.
+    #[default]
+    Expand,
+    /// 참조를 그대로 기록합니다. 환경 변수를 직접 풀어 주는 에이전트에 사용합니다.
+    Passthrough,
+}
+
+impl EnvMode {
+    pub const ALL: &'static [EnvMode] = &[EnvMode::Expand, EnvMode::Passthrough];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EnvMode::Expand => "expand",
+            EnvMode::Passthrough => "passthrough",
+        }
+    }
+}
+
+impl fmt::Display for EnvMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EnvMode {
+    type Err = BackendError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        EnvMode::ALL
+            .iter()
+            .copied()
+            .find(|mode| mode.as_str() == value)
+            .ok_or_else(|| {
+                BackendError::Other(format!(
+                    "unknown env mode '{value}' (expected expand or passthrough)"
+                ))
+            })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolConfiguration {
     pub name: String,
+    pub kind: AgentKind,
     pub version: Option<String>,
     pub config_path: String,
+    /// 프로젝트 단위 설정 파일이면 해당 프로젝트
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub policy: SyncPolicy,
+    #[serde(default)]
+    pub env_mode: EnvMode,
+    /// 동기화에 쓰는 프로필. 없으면 기본 프로필입니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
     pub settings: McpSettings,
 }
 
 impl ToolConfiguration {
     pub fn new(
         name: impl Into<String>,
+        kind: AgentKind,
         config_path: impl Into<String>,
         settings: McpSettings,
     ) -> Self {
         Self {
             name: name.into(),
+            kind,
             version: None,
             config_path: config_path.into(),
+            project: None,
+            policy: SyncPolicy::default(),
+            env_mode: EnvMode::default(),
+            profile: None,
             settings,
         }
     }
@@ -108,6 +373,9 @@ impl ToolConfiguration {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncSummary {
+    /// `sync_history` 에 기록된 뒤 부여되는 ID
+    #[serde(default)]
+    pub id: Option<i64>,
     pub tool: String,
     pub status: SyncStatus,
     pub message: String,
@@ -120,16 +388,206 @@ pub enum SyncStatus {
     Updated,
     Skipped,
     Failed,
+    /// 마지막 동기화 이후 마스터와 도구가 같은 필드를 서로 다르게 바꿨습니다.
+    Conflict,
+}
+
+/// 마지막 동기화 이후 마스터와 도구 양쪽에서 바뀐 필드. `field` 가 없으면 도구에서 삭제한
+/// 서버를 마스터가 수정한 경우입니다. 값이 없는 쪽은 `null` 입니다.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SyncConflict {
+    pub server: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub master: Value,
+    pub tool: Value,
+}
+
+impl fmt::Display for SyncConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "{}.{}", self.server, field),
+            None => write!(f, "{} (removed in tool)", self.server),
+        }
+    }
+}
+
+/// 충돌을 해결할 때 남길 쪽
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictSide {
+    Master,
+    Tool,
+}
+
+impl FromStr for ConflictSide {
+    type Err = BackendError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "master" => Ok(ConflictSide::Master),
+            "tool" => Ok(ConflictSide::Tool),
+            _ => Err(BackendError::Other(format!(
+                "unknown conflict side '{value}' (expected master or tool)"
+            ))),
+        }
+    }
+}
+
+/// 드라이런 결과. `proposed` 는 동기화 시 기록될 파일 전체 내용입니다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPlan {
+    pub tool: String,
+    pub config_path: String,
+    pub changed: bool,
+    pub proposed: String,
+    /// 자격 증명을 가린 구성으로 렌더링한 `proposed`. 출력 전에 `Redact` 가 `proposed` 와 바꿉니다.
+    #[serde(skip)]
+    pub proposed_redacted: String,
+    pub changes: SettingsDiff,
+    /// 에이전트가 지원하지 않아 변환하거나 생략한 항목
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// 도구 쪽 값을 그대로 둔 충돌 필드
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<SyncConflict>,
+    /// 도구 설정을 읽지 못해 계획을 세우지 못한 이유
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SyncPlan {
+    /// 계획을 세우지 못한 도구를 다른 도구의 계획과 함께 보고할 때 씁니다.
+    pub fn failed(
+        tool: impl Into<String>,
+        config_path: impl Into<String>,
+        error: impl Into<String>,
+    ) -> Self {
+        Self {
+            tool: tool.into(),
+            config_path: config_path.into(),
+            changed: false,
+            proposed: String::new(),
+            proposed_redacted: String::new(),
+            changes: SettingsDiff::default(),
+            warnings: Vec::new(),
+            conflicts: Vec::new(),
+            error: Some(error.into()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncRequest {
     pub tool: Option<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SyncResponse {
+    Applied(Vec<SyncSummary>),
+    Planned(Vec<SyncPlan>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatePolicyRequest {
+    pub policy: SyncPolicy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateEnvModeRequest {
+    pub env_mode: EnvMode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolveConflictRequest {
+    pub server: String,
+    pub take: ConflictSide,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RestoreRequest {
+    /// 지정하지 않으면 가장 최근 백업을 복원합니다.
+    #[serde(default)]
+    pub sync_id: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateMasterRequest {
     pub settings: McpSettings,
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+/// 마스터 구성 리비전이 어느 경로로 만들어졌는지 나타냅니다.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RevisionSource {
+    Api,
+    Cli,
+    Import,
+    System,
+}
+
+impl RevisionSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RevisionSource::Api => "api",
+            RevisionSource::Cli => "cli",
+            RevisionSource::Import => "import",
+            RevisionSource::System => "system",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "api" => RevisionSource::Api,
+            "cli" => RevisionSource::Cli,
+            "import" => RevisionSource::Import,
+            _ => RevisionSource::System,
+        }
+    }
+}
+
+/// 마스터 구성을 저장할 때 함께 기록할 리비전 정보
+#[derive(Debug, Clone)]
+pub struct RevisionMeta {
+    pub source: RevisionSource,
+    pub author: Option<String>,
+    pub message: Option<String>,
+}
+
+impl RevisionMeta {
+    pub fn new(source: RevisionSource, author: Option<String>) -> Self {
+        Self {
+            source,
+            author,
+            message: None,
+        }
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MasterRevision {
+    pub id: i64,
+    pub source: RevisionSource,
+    pub author: Option<String>,
+    pub message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub settings: McpSettings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RollbackRequest {
+    #[serde(default)]
+    pub author: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -138,6 +596,40 @@ pub struct MasterConfigResponse {
     pub updated_at: DateTime<Utc>,
 }
 
+/// 프로필을 지정하지 않은 도구가 쓰는 프로필. `master_config` 의 마스터 구성입니다.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// 이름 있는 마스터 구성. 도구마다 동기화에 쓸 프로필을 고를 수 있습니다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub settings: McpSettings,
+    pub updated_at: DateTime<Utc>,
+    /// 이 프로필로 동기화하는 도구
+    #[serde(default)]
+    pub tools: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateProfileRequest {
+    pub name: String,
+    /// 생략하면 기본 프로필을 복사합니다.
+    #[serde(default)]
+    pub settings: Option<McpSettings>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateProfileRequest {
+    pub settings: McpSettings,
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignProfileRequest {
+    pub profile: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportRecommendedRequest {
     pub server_id: String,
@@ -1,32 +1,103 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
 use parking_lot::Mutex;
 use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
 
+use crate::adapter::{self, AgentKind};
 use crate::config::{
-    default_recommended_servers, MasterConfigResponse, McpSettings, RecommendedServer, SyncStatus,
-    SyncSummary,
+    default_recommended_servers, EnvMode, MasterConfigResponse, MasterRevision, McpSettings,
+    Profile, RecommendedServer, RevisionMeta, RevisionSource, SyncPolicy, SyncStatus, SyncSummary,
+    DEFAULT_PROFILE,
 };
 use crate::error::{BackendError, BackendResult};
+use crate::secrets::{self, SecretKey, Secrets};
 
-const DB_FILE: &str = "../database/mcp_sync.db";
+/// 동기화로 덮어쓰기 전의 도구 설정 파일 스냅샷
+#[derive(Debug, Clone)]
+pub struct ConfigBackup {
+    pub tool: String,
+    /// 파일을 덮어쓴 뒤 동기화 기록 전에 중단되었으면 `None` 입니다.
+    pub sync_id: Option<i64>,
+    pub config_path: PathBuf,
+    pub content: Option<String>,
+}
+
+/// 마지막 동기화 때 도구에 기록한 구성. 다음 동기화에서 3-way 병합의 기준이 됩니다.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncBase {
+    /// 마스터가 도구에 기록하려던 구성. 환경 변수는 `${env:...}` 참조 형태입니다.
+    pub settings: McpSettings,
+    /// `expand` 방식 도구에 마스터의 환경 변수를 풀어 기록한 구성. 암호화해 저장합니다.
+    pub expanded: Option<McpSettings>,
+}
+
+/// 등록된 동기화 대상. 프로젝트 단위 설정 파일은 같은 에이전트의 별도 대상으로 등록됩니다.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolRecord {
+    pub name: String,
+    pub config_path: PathBuf,
+    /// 어댑터를 고를 때 쓰는 에이전트 이름. 전역 설정이면 `name` 과 같습니다.
+    pub agent: String,
+    /// 알려진 설정 위치에서 찾은 도구의 형식. 없으면 `agent` 와 파일 내용으로 고릅니다.
+    pub kind: Option<AgentKind>,
+    /// 프로젝트 단위 설정이면 마스터 `project_overrides` 의 `project` 값
+    pub project: Option<String>,
+    pub policy: SyncPolicy,
+    pub env_mode: EnvMode,
+    /// 동기화에 쓰는 프로필. 없으면 기본 프로필(마스터 구성)입니다.
+    pub profile: Option<String>,
+}
+
+impl ToolRecord {
+    pub fn new(name: impl Into<String>, config_path: impl Into<PathBuf>) -> Self {
+        let name = name.into();
+        Self {
+            agent: name.clone(),
+            kind: None,
+            name,
+            config_path: config_path.into(),
+            project: None,
+            policy: SyncPolicy::default(),
+            env_mode: EnvMode::default(),
+            profile: None,
+        }
+    }
+
+    /// 설정 파일 `content` 를 읽고 쓸 어댑터 종류
+    pub fn agent_kind(&self, content: &str) -> AgentKind {
+        self.kind
+            .unwrap_or_else(|| adapter::select_adapter(&self.agent, content))
+    }
+}
 
 pub struct Database {
     conn: Mutex<Connection>,
+    /// 비밀 저장소 암호화 키 파일. 데이터베이스 파일 옆의 `.key` 파일입니다.
+    secret_key_path: PathBuf,
+}
+
+/// 저장된 비밀의 이름과 갱신 시각. 값은 `Database::get_secret` 으로만 꺼낼 수 있습니다.
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretEntry {
+    pub name: String,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl Database {
-    pub fn initialize() -> BackendResult<Self> {
-        let path = Path::new(DB_FILE);
+    pub fn initialize(path: &Path) -> BackendResult<Self> {
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
         let conn = Connection::open(path)?;
         let db = Self {
             conn: Mutex::new(conn),
+            secret_key_path: path.with_extension("key"),
         };
         db.setup()?;
+        db.seal_plain_backups()?;
         db.seed_recommended_servers()?;
         Ok(db)
     }
@@ -42,6 +113,29 @@ impl Database {
             [],
         )?;
 
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS master_config_revisions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                content TEXT NOT NULL,
+                source TEXT NOT NULL,
+                author TEXT,
+                message TEXT,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        // 이름 있는 프로필의 리비전은 `profile` 에 이름을 남기고, 기본 프로필은 `NULL` 입니다.
+        add_column_if_missing(&conn, "master_config_revisions", "profile", "TEXT")?;
+
+        // 리비전 기록 이전에 만들어진 데이터베이스는 현재 마스터 구성을 첫 리비전으로 남깁니다.
+        conn.execute(
+            "INSERT INTO master_config_revisions (content, source, author, message, created_at) \
+             SELECT content, 'system', NULL, 'existing master configuration', updated_at \
+             FROM master_config \
+             WHERE NOT EXISTS (SELECT 1 FROM master_config_revisions)",
+            [],
+        )?;
+
         conn.execute(
             "CREATE TABLE IF NOT EXISTS tools (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -51,6 +145,21 @@ impl Database {
             )",
             [],
         )?;
+        add_column_if_missing(&conn, "tools", "agent", "TEXT")?;
+        add_column_if_missing(&conn, "tools", "project", "TEXT")?;
+        add_column_if_missing(&conn, "tools", "policy", "TEXT")?;
+        add_column_if_missing(&conn, "tools", "env_mode", "TEXT")?;
+        add_column_if_missing(&conn, "tools", "profile", "TEXT")?;
+        add_column_if_missing(&conn, "tools", "kind", "TEXT")?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS profiles (
+                name TEXT PRIMARY KEY,
+                content TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
 
         conn.execute(
             "CREATE TABLE IF NOT EXISTS sync_history (
@@ -63,6 +172,40 @@ impl Database {
             [],
         )?;
 
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS config_backups (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                tool_name TEXT NOT NULL,
+                sync_id INTEGER,
+                config_path TEXT NOT NULL,
+                content TEXT,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        // 스냅샷에는 풀린 비밀 값이 들어 있으므로 비밀 저장소와 같은 키로 암호화해 `ciphertext` 에 둡니다.
+        // `content` 는 암호화 이전에 저장된 백업에만 남습니다.
+        add_column_if_missing(&conn, "config_backups", "ciphertext", "BLOB")?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS secrets (
+                name TEXT PRIMARY KEY,
+                ciphertext BLOB NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS sync_bases (
+                tool_name TEXT PRIMARY KEY,
+                content TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        add_column_if_missing(&conn, "sync_bases", "expanded", "BLOB")?;
+
         conn.execute(
             "CREATE TABLE IF NOT EXISTS recommended_servers (
                 id TEXT PRIMARY KEY,
@@ -106,16 +249,99 @@ impl Database {
         Ok(())
     }
 
-    pub fn upsert_master_config(&self, settings: &McpSettings) -> BackendResult<()> {
-        let conn = self.conn.lock();
-        let now = Utc::now();
+    /// 마스터 구성을 갱신하고 같은 트랜잭션에서 리비전을 남깁니다. 새 리비전 ID 를 반환합니다.
+    pub fn upsert_master_config(
+        &self,
+        settings: &McpSettings,
+        meta: &RevisionMeta,
+    ) -> BackendResult<i64> {
+        let mut conn = self.conn.lock();
+        let now = Utc::now().to_rfc3339();
         let content = serde_json::to_string(settings)?;
-        conn.execute(
+        let tx = conn.transaction()?;
+        tx.execute(
             "INSERT INTO master_config (id, content, updated_at) VALUES (1, ?, ?) \
              ON CONFLICT(id) DO UPDATE SET content = excluded.content, updated_at = excluded.updated_at",
-            params![content, now.to_rfc3339()],
+            params![content, now],
         )?;
-        Ok(())
+        let revision = insert_revision(&tx, None, &content, meta, &now)?;
+        tx.commit()?;
+        Ok(revision)
+    }
+
+    pub fn list_master_revisions(&self, limit: usize) -> BackendResult<Vec<MasterRevision>> {
+        self.list_profile_revisions(DEFAULT_PROFILE, limit)
+    }
+
+    pub fn get_master_revision(&self, id: i64) -> BackendResult<Option<MasterRevision>> {
+        self.get_profile_revision(DEFAULT_PROFILE, id)
+    }
+
+    pub fn list_profile_revisions(
+        &self,
+        name: &str,
+        limit: usize,
+    ) -> BackendResult<Vec<MasterRevision>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, content, source, author, message, created_at FROM master_config_revisions \
+             WHERE profile IS ? ORDER BY id DESC LIMIT ?",
+        )?;
+        let rows = stmt.query_map(
+            params![revision_profile(name), limit as i64],
+            revision_from_row,
+        )?;
+        let mut revisions = Vec::new();
+        for row in rows {
+            revisions.push(parse_revision(row?)?);
+        }
+        Ok(revisions)
+    }
+
+    pub fn get_profile_revision(
+        &self,
+        name: &str,
+        id: i64,
+    ) -> BackendResult<Option<MasterRevision>> {
+        let conn = self.conn.lock();
+        let row = conn
+            .query_row(
+                "SELECT id, content, source, author, message, created_at FROM master_config_revisions \
+                 WHERE id = ? AND profile IS ?",
+                params![id, revision_profile(name)],
+                revision_from_row,
+            )
+            .optional()?;
+        row.map(parse_revision).transpose()
+    }
+
+    /// 프로필의 지정한 리비전을 새 리비전으로 다시 저장합니다. 기본 프로필은 마스터 구성입니다.
+    pub fn rollback_profile(
+        &self,
+        name: &str,
+        revision: i64,
+        meta: RevisionMeta,
+    ) -> BackendResult<McpSettings> {
+        let target = self
+            .get_profile_revision(name, revision)?
+            .ok_or_else(|| BackendError::NotFound(format!("revision #{revision} of '{name}'")))?;
+        let meta = meta.with_message(format!("rollback to revision #{revision}"));
+        self.save_profile(name, &target.settings, &meta)?;
+        Ok(target.settings)
+    }
+
+    /// 지정한 리비전의 내용을 새 리비전으로 다시 저장합니다. 이전 리비전은 지우지 않습니다.
+    pub fn rollback_master_config(
+        &self,
+        revision: i64,
+        meta: RevisionMeta,
+    ) -> BackendResult<MasterConfigResponse> {
+        let target = self
+            .get_master_revision(revision)?
+            .ok_or_else(|| BackendError::NotFound(format!("master revision #{revision}")))?;
+        let meta = meta.with_message(format!("rollback to revision #{revision}"));
+        self.upsert_master_config(&target.settings, &meta)?;
+        self.get_master_config()
     }
 
     pub fn get_master_config(&self) -> BackendResult<MasterConfigResponse> {
@@ -152,34 +378,45 @@ impl Database {
             Err(BackendError::ConfigNotFound) => {
                 let default = McpSettings {
                     servers: vec![Default::default()],
-                    project_overrides: vec![],
+                    ..Default::default()
                 };
-                self.upsert_master_config(&default)?;
+                self.upsert_master_config(
+                    &default,
+                    &RevisionMeta::new(RevisionSource::System, None)
+                        .with_message("default master configuration"),
+                )?;
                 self.get_master_config()
             }
             Err(err) => Err(err),
         }
     }
 
-    pub fn record_tool_detection(&self, name: &str, config_path: &Path) -> BackendResult<()> {
+    pub fn record_tool_detection(&self, tool: &ToolRecord) -> BackendResult<()> {
         let conn = self.conn.lock();
         let now = Utc::now();
         conn.execute(
-            "INSERT INTO tools (name, config_path, last_detected_at) VALUES (?, ?, ?) \
-             ON CONFLICT(name) DO UPDATE SET config_path = excluded.config_path, last_detected_at = excluded.last_detected_at",
-            params![name, config_path.to_string_lossy(), now.to_rfc3339()],
+            "INSERT INTO tools (name, config_path, agent, kind, project, last_detected_at) \
+             VALUES (?, ?, ?, ?, ?, ?) \
+             ON CONFLICT(name) DO UPDATE SET config_path = excluded.config_path, agent = excluded.agent, \
+             kind = excluded.kind, project = excluded.project, last_detected_at = excluded.last_detected_at",
+            params![
+                tool.name,
+                tool.config_path.to_string_lossy(),
+                tool.agent,
+                tool.kind.map(AgentKind::as_str),
+                tool.project,
+                now.to_rfc3339()
+            ],
         )?;
         Ok(())
     }
 
-    pub fn list_tools(&self) -> BackendResult<Vec<(String, PathBuf)>> {
+    pub fn list_tools(&self) -> BackendResult<Vec<ToolRecord>> {
         let conn = self.conn.lock();
-        let mut stmt = conn.prepare("SELECT name, config_path FROM tools ORDER BY name")?;
-        let rows = stmt.query_map([], |row| {
-            let name: String = row.get(0)?;
-            let path: String = row.get(1)?;
-            Ok((name, PathBuf::from(path)))
-        })?;
+        let mut stmt = conn.prepare(
+            "SELECT name, config_path, agent, project, policy, env_mode, profile, kind FROM tools ORDER BY name",
+        )?;
+        let rows = stmt.query_map([], tool_from_row)?;
         let mut tools = Vec::new();
         for row in rows {
             tools.push(row?);
@@ -187,44 +424,500 @@ impl Database {
         Ok(tools)
     }
 
-    pub fn record_sync(&self, summary: &SyncSummary) -> BackendResult<()> {
+    pub fn find_tool(&self, name: &str) -> BackendResult<Option<ToolRecord>> {
+        let conn = self.conn.lock();
+        let row = conn
+            .query_row(
+                "SELECT name, config_path, agent, project, policy, env_mode, profile, kind FROM tools WHERE name = ?",
+                params![name],
+                tool_from_row,
+            )
+            .optional()?;
+        Ok(row)
+    }
+
+    /// 등록되지 않은 도구면 `NotFound` 를 반환합니다.
+    pub fn set_tool_policy(&self, name: &str, policy: SyncPolicy) -> BackendResult<()> {
+        let conn = self.conn.lock();
+        let updated = conn.execute(
+            "UPDATE tools SET policy = ? WHERE name = ?",
+            params![policy.as_str(), name],
+        )?;
+        if updated == 0 {
+            return Err(BackendError::NotFound(format!("tool '{name}'")));
+        }
+        Ok(())
+    }
+
+    /// 등록되지 않은 도구면 `NotFound` 를 반환합니다.
+    pub fn set_tool_env_mode(&self, name: &str, mode: EnvMode) -> BackendResult<()> {
+        let conn = self.conn.lock();
+        let updated = conn.execute(
+            "UPDATE tools SET env_mode = ? WHERE name = ?",
+            params![mode.as_str(), name],
+        )?;
+        if updated == 0 {
+            return Err(BackendError::NotFound(format!("tool '{name}'")));
+        }
+        Ok(())
+    }
+
+    /// 등록되지 않은 도구나 없는 프로필이면 `NotFound` 를 반환합니다.
+    pub fn set_tool_profile(&self, name: &str, profile: &str) -> BackendResult<()> {
+        let stored = if profile == DEFAULT_PROFILE {
+            None
+        } else {
+            self.profile_settings(Some(profile))?;
+            Some(profile)
+        };
+        let conn = self.conn.lock();
+        let updated = conn.execute(
+            "UPDATE tools SET profile = ? WHERE name = ?",
+            params![stored, name],
+        )?;
+        if updated == 0 {
+            return Err(BackendError::NotFound(format!("tool '{name}'")));
+        }
+        Ok(())
+    }
+
+    /// 기본 프로필(마스터 구성)을 먼저, 이름 있는 프로필을 이름순으로 반환합니다.
+    pub fn list_profiles(&self) -> BackendResult<Vec<Profile>> {
+        let master = self.ensure_master_config()?;
+        let tools = self.list_tools()?;
+        let users = |profile: &str| -> Vec<String> {
+            tools
+                .iter()
+                .filter(|tool| tool.profile.as_deref().unwrap_or(DEFAULT_PROFILE) == profile)
+                .map(|tool| tool.name.clone())
+                .collect()
+        };
+        let rows: Vec<(String, String, String)> = {
+            let conn = self.conn.lock();
+            let mut stmt =
+                conn.prepare("SELECT name, content, updated_at FROM profiles ORDER BY name")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+
+        let mut profiles = vec![Profile {
+            name: DEFAULT_PROFILE.to_string(),
+            settings: master.settings,
+            updated_at: master.updated_at,
+            tools: users(DEFAULT_PROFILE),
+        }];
+        for (name, content, updated_at) in rows {
+            let updated_at = DateTime::parse_from_rfc3339(&updated_at)
+                .map_err(|err| BackendError::Other(err.to_string()))?
+                .with_timezone(&Utc);
+            profiles.push(Profile {
+                settings: serde_json::from_str(&content)?,
+                updated_at,
+                tools: users(&name),
+                name,
+            });
+        }
+        Ok(profiles)
+    }
+
+    pub fn get_profile(&self, name: &str) -> BackendResult<Option<Profile>> {
+        Ok(self
+            .list_profiles()?
+            .into_iter()
+            .find(|profile| profile.name == name))
+    }
+
+    /// 도구가 동기화에 쓸 마스터 구성. `None` 이면 기본 프로필입니다.
+    pub fn profile_settings(&self, profile: Option<&str>) -> BackendResult<McpSettings> {
+        let Some(name) = profile.filter(|name| *name != DEFAULT_PROFILE) else {
+            return Ok(self.ensure_master_config()?.settings);
+        };
+        let content: Option<String> = {
+            let conn = self.conn.lock();
+            conn.query_row(
+                "SELECT content FROM profiles WHERE name = ?",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?
+        };
+        match content {
+            Some(content) => Ok(serde_json::from_str(&content)?),
+            None => Err(BackendError::NotFound(format!("profile '{name}'"))),
+        }
+    }
+
+    pub fn create_profile(&self, name: &str, settings: &McpSettings) -> BackendResult<()> {
+        validate_profile_name(name)?;
+        let conn = self.conn.lock();
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO profiles (name, content, updated_at) VALUES (?, ?, ?)",
+            params![
+                name,
+                serde_json::to_string(settings)?,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        if inserted == 0 {
+            return Err(BackendError::InvalidConfig(format!(
+                "profile '{name}' already exists"
+            )));
+        }
+        Ok(())
+    }
+
+    /// 마스터 구성처럼 프로필마다 리비전을 남깁니다. 기본 프로필은 마스터 구성입니다.
+    pub fn save_profile(
+        &self,
+        name: &str,
+        settings: &McpSettings,
+        meta: &RevisionMeta,
+    ) -> BackendResult<()> {
+        if name == DEFAULT_PROFILE {
+            self.upsert_master_config(settings, meta)?;
+            return Ok(());
+        }
+        let mut conn = self.conn.lock();
+        let now = Utc::now().to_rfc3339();
+        let content = serde_json::to_string(settings)?;
+        let tx = conn.transaction()?;
+        // 리비전 기록 이전에 만들었거나 처음 수정하는 프로필은 현재 내용을 첫 리비전으로 남깁니다.
+        tx.execute(
+            "INSERT INTO master_config_revisions (content, source, author, message, created_at, profile) \
+             SELECT content, 'system', NULL, 'existing profile', updated_at, name FROM profiles \
+             WHERE name = ?1 AND NOT EXISTS (SELECT 1 FROM master_config_revisions WHERE profile = ?1)",
+            params![name],
+        )?;
+        let updated = tx.execute(
+            "UPDATE profiles SET content = ?, updated_at = ? WHERE name = ?",
+            params![content, now, name],
+        )?;
+        if updated == 0 {
+            return Err(BackendError::NotFound(format!("profile '{name}'")));
+        }
+        insert_revision(&tx, Some(name), &content, meta, &now)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 기본 프로필과 도구가 사용 중인 프로필은 삭제하지 않습니다.
+    pub fn remove_profile(&self, name: &str) -> BackendResult<()> {
+        if name == DEFAULT_PROFILE {
+            return Err(BackendError::InvalidConfig(
+                "the default profile cannot be removed".to_string(),
+            ));
+        }
         let conn = self.conn.lock();
+        let users: Vec<String> = {
+            let mut stmt =
+                conn.prepare("SELECT name FROM tools WHERE profile = ? ORDER BY name")?;
+            let rows = stmt.query_map(params![name], |row| row.get(0))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        if !users.is_empty() {
+            return Err(BackendError::InvalidConfig(format!(
+                "profile '{name}' is used by {}",
+                users.join(", ")
+            )));
+        }
+        let removed = conn.execute("DELETE FROM profiles WHERE name = ?", params![name])?;
+        if removed == 0 {
+            return Err(BackendError::NotFound(format!("profile '{name}'")));
+        }
+        // 같은 이름으로 다시 만든 프로필에 이전 기록이 섞이지 않게 합니다.
         conn.execute(
-            "INSERT INTO sync_history (tool_name, status, message, synced_at) VALUES (?, ?, ?, ?)",
+            "DELETE FROM master_config_revisions WHERE profile = ?",
+            params![name],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_sync(&self, summary: &SyncSummary) -> BackendResult<i64> {
+        let conn = self.conn.lock();
+        insert_sync(&conn, summary)
+    }
+
+    /// 파일을 덮어쓰기 전에 기존 내용을 저장합니다. 파일이 없었다면 `content` 는 `None` 입니다.
+    /// 쓰기가 끝나면 `record_sync_run` 으로 동기화 기록과 연결하고, 실패하면 `discard_backup` 으로 지웁니다.
+    /// `content` 는 암호화해 저장합니다. `None` 은 덮어쓰기 전 파일이 없었다는 뜻입니다.
+    pub fn record_backup(
+        &self,
+        tool_name: &str,
+        config_path: &Path,
+        content: Option<&str>,
+    ) -> BackendResult<i64> {
+        let sealed = match content {
+            Some(content) => {
+                Some(SecretKey::load_or_create(&self.secret_key_path)?.encrypt(content)?)
+            }
+            None => None,
+        };
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO config_backups (tool_name, sync_id, config_path, ciphertext, created_at) \
+             VALUES (?, NULL, ?, ?, ?)",
             params![
-                summary.tool,
-                format!("{:?}", summary.status).to_lowercase(),
-                summary.message,
-                summary.synced_at.to_rfc3339(),
+                tool_name,
+                config_path.to_string_lossy(),
+                sealed,
+                Utc::now().to_rfc3339(),
             ],
         )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// 암호화 이전에 평문으로 저장된 백업을 암호화합니다. 그런 백업이 없으면 키 파일을 만들지 않습니다.
+    fn seal_plain_backups(&self) -> BackendResult<()> {
+        let plain: Vec<(i64, String)> = {
+            let conn = self.conn.lock();
+            let mut stmt =
+                conn.prepare("SELECT id, content FROM config_backups WHERE content IS NOT NULL")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        if plain.is_empty() {
+            return Ok(());
+        }
+        let key = SecretKey::load_or_create(&self.secret_key_path)?;
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        for (id, content) in plain {
+            tx.execute(
+                "UPDATE config_backups SET ciphertext = ?, content = NULL WHERE id = ?",
+                params![key.encrypt(&content)?, id],
+            )?;
+        }
+        tx.commit()?;
         Ok(())
     }
 
+    pub fn discard_backup(&self, backup_id: i64) -> BackendResult<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "DELETE FROM config_backups WHERE id = ?",
+            params![backup_id],
+        )?;
+        Ok(())
+    }
+
+    /// 동기화 결과를 기록하고, 같은 트랜잭션에서 새 동기화 기준을 저장하고 쓰기 전 백업을 연결합니다.
+    pub fn record_sync_run(
+        &self,
+        summary: &SyncSummary,
+        base: Option<&SyncBase>,
+        backup_id: Option<i64>,
+    ) -> BackendResult<i64> {
+        let base = base.map(|base| self.seal_sync_base(base)).transpose()?;
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        if let Some((content, expanded)) = base {
+            upsert_sync_base(&tx, &summary.tool, &content, expanded.as_deref())?;
+        }
+        let sync_id = insert_sync(&tx, summary)?;
+        if let Some(backup_id) = backup_id {
+            tx.execute(
+                "UPDATE config_backups SET sync_id = ? WHERE id = ?",
+                params![sync_id, backup_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(sync_id)
+    }
+
+    /// `sync_id` 가 없으면 해당 도구의 가장 최근 백업을 반환합니다. 파일을 쓴 뒤 기록하지 못한
+    /// 동기화의 백업도 포함되므로, 중단된 동기화 직전 상태로도 되돌릴 수 있습니다.
+    pub fn find_backup(
+        &self,
+        tool_name: &str,
+        sync_id: Option<i64>,
+    ) -> BackendResult<Option<ConfigBackup>> {
+        let conn = self.conn.lock();
+        let map_row = |row: &rusqlite::Row<'_>| {
+            let path: String = row.get(2)?;
+            let backup = ConfigBackup {
+                tool: row.get(0)?,
+                sync_id: row.get(1)?,
+                config_path: PathBuf::from(path),
+                content: row.get(3)?,
+            };
+            Ok((backup, row.get::<_, Option<Vec<u8>>>(4)?))
+        };
+        let row = match sync_id {
+            Some(sync_id) => conn
+                .query_row(
+                    "SELECT tool_name, sync_id, config_path, content, ciphertext FROM config_backups \
+                     WHERE tool_name = ? AND sync_id = ? ORDER BY id DESC LIMIT 1",
+                    params![tool_name, sync_id],
+                    map_row,
+                )
+                .optional()?,
+            None => conn
+                .query_row(
+                    "SELECT tool_name, sync_id, config_path, content, ciphertext FROM config_backups \
+                     WHERE tool_name = ? ORDER BY id DESC LIMIT 1",
+                    params![tool_name],
+                    map_row,
+                )
+                .optional()?,
+        };
+        drop(conn);
+        let Some((mut backup, sealed)) = row else {
+            return Ok(None);
+        };
+        if let Some(sealed) = sealed {
+            let key = SecretKey::load_or_create(&self.secret_key_path)?;
+            backup.content = Some(key.decrypt(&sealed)?);
+        }
+        Ok(Some(backup))
+    }
+
+    pub fn find_sync_base(&self, tool_name: &str) -> BackendResult<Option<SyncBase>> {
+        let row: Option<(String, Option<Vec<u8>>)> = {
+            let conn = self.conn.lock();
+            conn.query_row(
+                "SELECT content, expanded FROM sync_bases WHERE tool_name = ?",
+                params![tool_name],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+        };
+        let Some((content, expanded)) = row else {
+            return Ok(None);
+        };
+        let expanded = match expanded {
+            Some(sealed) => {
+                let key = SecretKey::load_or_create(&self.secret_key_path)?;
+                Some(serde_json::from_str(&key.decrypt(&sealed)?)?)
+            }
+            None => None,
+        };
+        Ok(Some(SyncBase {
+            settings: serde_json::from_str(&content)?,
+            expanded,
+        }))
+    }
+
+    pub fn record_sync_base(&self, tool_name: &str, base: &SyncBase) -> BackendResult<()> {
+        let (content, expanded) = self.seal_sync_base(base)?;
+        let conn = self.conn.lock();
+        upsert_sync_base(&conn, tool_name, &content, expanded.as_deref())
+    }
+
+    /// 풀어 기록한 구성에는 환경 변수의 자격 증명이 들어 있을 수 있어 암호화합니다.
+    fn seal_sync_base(&self, base: &SyncBase) -> BackendResult<(String, Option<Vec<u8>>)> {
+        let expanded = match &base.expanded {
+            Some(expanded) => Some(
+                SecretKey::load_or_create(&self.secret_key_path)?
+                    .encrypt(&serde_json::to_string(expanded)?)?,
+            ),
+            None => None,
+        };
+        Ok((serde_json::to_string(&base.settings)?, expanded))
+    }
+
+    pub fn set_secret(&self, name: &str, value: &str) -> BackendResult<()> {
+        secrets::validate_name(name)?;
+        let ciphertext = SecretKey::load_or_create(&self.secret_key_path)?.encrypt(value)?;
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO secrets (name, ciphertext, updated_at) VALUES (?, ?, ?)
+             ON CONFLICT(name) DO UPDATE SET ciphertext = excluded.ciphertext, updated_at = excluded.updated_at",
+            params![name, ciphertext, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_secret(&self, name: &str) -> BackendResult<Option<String>> {
+        let ciphertext: Option<Vec<u8>> = {
+            let conn = self.conn.lock();
+            conn.query_row(
+                "SELECT ciphertext FROM secrets WHERE name = ?",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?
+        };
+        match ciphertext {
+            Some(ciphertext) => {
+                let key = SecretKey::load_or_create(&self.secret_key_path)?;
+                Ok(Some(key.decrypt(&ciphertext)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn remove_secret(&self, name: &str) -> BackendResult<()> {
+        let conn = self.conn.lock();
+        let removed = conn.execute("DELETE FROM secrets WHERE name = ?", params![name])?;
+        if removed == 0 {
+            return Err(BackendError::NotFound(format!("secret '{name}'")));
+        }
+        Ok(())
+    }
+
+    pub fn list_secrets(&self) -> BackendResult<Vec<SecretEntry>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare("SELECT name, updated_at FROM secrets ORDER BY name")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut entries = Vec::new();
+        for row in rows {
+            let (name, updated_at) = row?;
+            let updated_at = DateTime::parse_from_rfc3339(&updated_at)
+                .map_err(|err| BackendError::Other(err.to_string()))?
+                .with_timezone(&Utc);
+            entries.push(SecretEntry { name, updated_at });
+        }
+        Ok(entries)
+    }
+
+    /// 모든 비밀을 복호화합니다. 비밀이 하나도 없으면 키 파일을 만들지 않습니다.
+    pub fn load_secrets(&self) -> BackendResult<Secrets> {
+        let sealed: Vec<(String, Vec<u8>)> = {
+            let conn = self.conn.lock();
+            let mut stmt = conn.prepare("SELECT name, ciphertext FROM secrets")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        if sealed.is_empty() {
+            return Ok(Secrets::default());
+        }
+        let key = SecretKey::load_or_create(&self.secret_key_path)?;
+        let mut values = BTreeMap::new();
+        for (name, ciphertext) in sealed {
+            values.insert(name, key.decrypt(&ciphertext)?);
+        }
+        Ok(Secrets::new(values))
+    }
+
     pub fn recent_sync_history(&self, limit: usize) -> BackendResult<Vec<SyncSummary>> {
         let conn = self.conn.lock();
         let mut stmt = conn.prepare(
-            "SELECT tool_name, status, message, synced_at FROM sync_history ORDER BY synced_at DESC LIMIT ?",
+            "SELECT id, tool_name, status, message, synced_at FROM sync_history ORDER BY synced_at DESC LIMIT ?",
         )?;
         let rows = stmt.query_map(params![limit as i64], |row| {
-            let tool: String = row.get(0)?;
-            let status: String = row.get(1)?;
-            let message: String = row.get(2)?;
-            let synced_at: String = row.get(3)?;
-            Ok((tool, status, message, synced_at))
+            let id: i64 = row.get(0)?;
+            let tool: String = row.get(1)?;
+            let status: String = row.get(2)?;
+            let message: String = row.get(3)?;
+            let synced_at: String = row.get(4)?;
+            Ok((id, tool, status, message, synced_at))
         })?;
         let mut items = Vec::new();
         for row in rows {
-            let (tool, status, message, synced_at) = row?;
+            let (id, tool, status, message, synced_at) = row?;
             let status = match status.as_str() {
                 "updated" => SyncStatus::Updated,
                 "skipped" => SyncStatus::Skipped,
+                "conflict" => SyncStatus::Conflict,
                 _ => SyncStatus::Failed,
             };
             let synced_at = DateTime::parse_from_rfc3339(&synced_at)
                 .map_err(|err| BackendError::Other(err.to_string()))?
                 .with_timezone(&Utc);
             items.push(SyncSummary {
+                id: Some(id),
                 tool,
                 status,
                 message,
@@ -298,3 +991,150 @@ impl Database {
         Ok(row)
     }
 }
+
+type RevisionRow = (i64, String, String, Option<String>, Option<String>, String);
+
+fn tool_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ToolRecord> {
+    let name: String = row.get(0)?;
+    let path: String = row.get(1)?;
+    // 컬럼이 추가되기 전에 등록된 도구는 이름을 에이전트 이름으로 사용합니다.
+    let agent: Option<String> = row.get(2)?;
+    Ok(ToolRecord {
+        agent: agent.unwrap_or_else(|| name.clone()),
+        name,
+        config_path: PathBuf::from(path),
+        project: row.get(3)?,
+        // 정책을 지정한 적 없는 도구는 기본 정책(additive)을 따릅니다.
+        policy: row
+            .get::<_, Option<String>>(4)?
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default(),
+        env_mode: row
+            .get::<_, Option<String>>(5)?
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default(),
+        profile: row.get(6)?,
+        kind: row
+            .get::<_, Option<String>>(7)?
+            .and_then(|value| value.parse().ok()),
+    })
+}
+
+fn validate_profile_name(name: &str) -> BackendResult<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if !valid {
+        return Err(BackendError::InvalidConfig(format!(
+            "invalid profile name '{name}' (use letters, digits, '_', '-' or '.')"
+        )));
+    }
+    if name == DEFAULT_PROFILE {
+        return Err(BackendError::InvalidConfig(format!(
+            "profile '{name}' already exists"
+        )));
+    }
+    Ok(())
+}
+
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> BackendResult<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(Result::ok)
+        .any(|name| name == column);
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn revision_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<RevisionRow> {
+    Ok((
+        row.get(0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+    ))
+}
+
+fn parse_revision(row: RevisionRow) -> BackendResult<MasterRevision> {
+    let (id, content, source, author, message, created_at) = row;
+    let created_at = DateTime::parse_from_rfc3339(&created_at)
+        .map_err(|err| BackendError::Other(err.to_string()))?
+        .with_timezone(&Utc);
+    Ok(MasterRevision {
+        id,
+        source: RevisionSource::parse(&source),
+        author,
+        message,
+        created_at,
+        settings: serde_json::from_str(&content)?,
+    })
+}
+
+/// 기본 프로필의 리비전은 `profile` 열이 `NULL` 입니다.
+fn revision_profile(name: &str) -> Option<&str> {
+    (name != DEFAULT_PROFILE).then_some(name)
+}
+
+fn insert_revision(
+    conn: &Connection,
+    profile: Option<&str>,
+    content: &str,
+    meta: &RevisionMeta,
+    now: &str,
+) -> BackendResult<i64> {
+    conn.execute(
+        "INSERT INTO master_config_revisions (content, source, author, message, created_at, profile) \
+         VALUES (?, ?, ?, ?, ?, ?)",
+        params![
+            content,
+            meta.source.as_str(),
+            meta.author,
+            meta.message,
+            now,
+            profile
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn insert_sync(conn: &Connection, summary: &SyncSummary) -> BackendResult<i64> {
+    conn.execute(
+        "INSERT INTO sync_history (tool_name, status, message, synced_at) VALUES (?, ?, ?, ?)",
+        params![
+            summary.tool,
+            format!("{:?}", summary.status).to_lowercase(),
+            summary.message,
+            summary.synced_at.to_rfc3339(),
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn upsert_sync_base(
+    conn: &Connection,
+    tool_name: &str,
+    content: &str,
+    expanded: Option<&[u8]>,
+) -> BackendResult<()> {
+    conn.execute(
+        "INSERT INTO sync_bases (tool_name, content, expanded, updated_at) VALUES (?, ?, ?, ?)
+         ON CONFLICT(tool_name) DO UPDATE SET content = excluded.content, expanded = excluded.expanded, \
+         updated_at = excluded.updated_at",
+        params![tool_name, content, expanded, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::adapter::AgentKind;
+use crate::config::{McpServer, McpSettings, ProjectOverride, ToolConfiguration};
+use crate::error::BackendResult;
+use crate::sync::{conform_settings, merge_settings, target_master, with_env_references};
+
+/// 마스터와 도구 구성 사이의 변경 목록. `Added` 는 도구에 없는 마스터 항목, `Removed` 는 도구에만 있는 항목입니다.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SettingsDiff {
+    pub servers: Vec<ServerChange>,
+    pub project_overrides: Vec<ProjectOverrideChange>,
+}
+
+impl SettingsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.servers.is_empty() && self.project_overrides.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum ServerChange {
+    Added {
+        id: String,
+        server: McpServer,
+    },
+    Removed {
+        id: String,
+        server: McpServer,
+    },
+    Modified {
+        id: String,
+        fields: Vec<FieldChange>,
+    },
+}
+
+impl ServerChange {
+    pub fn id(&self) -> &str {
+        match self {
+            ServerChange::Added { id, .. }
+            | ServerChange::Removed { id, .. }
+            | ServerChange::Modified { id, .. } => id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum ProjectOverrideChange {
+    Added {
+        project: String,
+        entry: ProjectOverride,
+    },
+    Removed {
+        project: String,
+        entry: ProjectOverride,
+    },
+    Modified {
+        project: String,
+        fields: Vec<FieldChange>,
+    },
+}
+
+/// 필드 하나의 마스터 값과 도구 값. 한쪽에 필드가 없으면 `null` 입니다.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub master: Value,
+    pub tool: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolDiff {
+    pub tool: String,
+    pub kind: AgentKind,
+    pub config_path: String,
+    pub in_sync: bool,
+    pub changes: SettingsDiff,
+    /// 에이전트가 지원하지 않아 변환하거나 생략한 항목
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// 마스터를 도구의 형식으로 한 번 렌더링한 뒤 다시 읽어, 형식이 표현하지 못하는 차이는 제외하고 비교합니다.
+pub fn diff_tool(tool: &ToolConfiguration, master: &McpSettings) -> BackendResult<ToolDiff> {
+    let adapter = tool.kind.adapter_for(Path::new(&tool.config_path));
+    let existing = fs::read_to_string(Path::new(&tool.config_path)).ok();
+    let master = &target_master(tool, master);
+    let tool = &with_env_references(tool, master);
+    // 동기화가 실제로 쓸 병합 결과를 기준으로 삼습니다. 도구에만 있는 항목은 정책이 유지하면
+    // (`additive`, `preserve_local`) 차이가 아니고, `mirror` 에서만 `removed` 로 보고됩니다.
+    let normalized = merge_settings(master, &tool.settings, tool.policy);
+    let (normalized, warnings) = conform_settings(&normalized, tool.kind);
+    let rendered = adapter.render(&normalized, existing.as_deref())?;
+    let expected = adapter.parse(&rendered)?;
+    let changes = diff_settings(&expected, &tool.settings);
+    Ok(ToolDiff {
+        tool: tool.name.clone(),
+        kind: tool.kind,
+        config_path: tool.config_path.clone(),
+        in_sync: changes.is_empty(),
+        changes,
+        warnings,
+    })
+}
+
+pub fn diff_settings(master: &McpSettings, tool: &McpSettings) -> SettingsDiff {
+    let tool_servers: HashMap<_, _> = tool
+        .servers
+        .iter()
+        .map(|server| (server.id.as_str(), server))
+        .collect();
+    let mut servers = Vec::new();
+    for server in &master.servers {
+        match tool_servers.get(server.id.as_str()) {
+            None => servers.push(ServerChange::Added {
+                id: server.id.clone(),
+                server: server.clone(),
+            }),
+            Some(current) => {
+                let fields = diff_fields(server, current, &["id"]);
+                if !fields.is_empty() {
+                    servers.push(ServerChange::Modified {
+                        id: server.id.clone(),
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+    for server in &tool.servers {
+        if !master.servers.iter().any(|item| item.id == server.id) {
+            servers.push(ServerChange::Removed {
+                id: server.id.clone(),
+                server: server.clone(),
+            });
+        }
+    }
+
+    let mut project_overrides = Vec::new();
+    for entry in &master.project_overrides {
+        match tool
+            .project_overrides
+            .iter()
+            .find(|item| item.project == entry.project)
+        {
+            None => project_overrides.push(ProjectOverrideChange::Added {
+                project: entry.project.clone(),
+                entry: entry.clone(),
+            }),
+            Some(current) => {
+                let fields = diff_fields(entry, current, &["project"]);
+                if !fields.is_empty() {
+                    project_overrides.push(ProjectOverrideChange::Modified {
+                        project: entry.project.clone(),
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+    for entry in &tool.project_overrides {
+        if !master
+            .project_overrides
+            .iter()
+            .any(|item| item.project == entry.project)
+        {
+            project_overrides.push(ProjectOverrideChange::Removed {
+                project: entry.project.clone(),
+                entry: entry.clone(),
+            });
+        }
+    }
+
+    SettingsDiff {
+        servers,
+        project_overrides,
+    }
+}
+
+fn diff_fields<T: Serialize>(master: &T, tool: &T, ignore: &[&str]) -> Vec<FieldChange> {
+    let master = to_object(master);
+    let tool = to_object(tool);
+    let mut fields = Vec::new();
+    let keys = master
+        .keys()
+        .chain(tool.keys().filter(|key| !master.contains_key(*key)));
+    for key in keys {
+        if ignore.contains(&key.as_str()) {
+            continue;
+        }
+        let before = tool.get(key).cloned().unwrap_or(Value::Null);
+        let after = master.get(key).cloned().unwrap_or(Value::Null);
+        if before != after {
+            fields.push(FieldChange {
+                field: key.clone(),
+                master: after,
+                tool: before,
+            });
+        }
+    }
+    fields
+}
+
+fn to_object<T: Serialize>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
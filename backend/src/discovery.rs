@@ -0,0 +1,218 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::adapter::AgentKind;
+
+/// 알려진 설정 경로를 계산할 때 기준이 되는 디렉터리들입니다.
+#[derive(Debug, Clone)]
+pub struct DiscoveryEnv {
+    pub home: PathBuf,
+    pub xdg_config_home: PathBuf,
+}
+
+impl DiscoveryEnv {
+    pub fn new(home: impl Into<PathBuf>) -> Self {
+        let home = home.into();
+        Self {
+            xdg_config_home: home.join(".config"),
+            home,
+        }
+    }
+
+    /// `$HOME` 과 `$XDG_CONFIG_HOME` 을 읽습니다. `$HOME` 이 없으면 `None` 입니다.
+    pub fn from_env() -> Option<Self> {
+        let home = env::var_os("HOME").filter(|value| !value.is_empty())?;
+        let mut discovery = Self::new(home);
+        if let Some(config) = env::var_os("XDG_CONFIG_HOME").filter(|value| !value.is_empty()) {
+            discovery.xdg_config_home = PathBuf::from(config);
+        }
+        Some(discovery)
+    }
+}
+
+/// 에이전트 하나의 Linux 설정 위치. `marker` 디렉터리만 있어도 설치된 것으로 보고,
+/// 설정 파일이 아직 없으면 첫 동기화 때 생성합니다.
+pub struct KnownLocation {
+    pub name: &'static str,
+    pub kind: AgentKind,
+    config: fn(&DiscoveryEnv) -> PathBuf,
+    marker: fn(&DiscoveryEnv) -> PathBuf,
+}
+
+impl KnownLocation {
+    pub fn config_path(&self, env: &DiscoveryEnv) -> PathBuf {
+        (self.config)(env)
+    }
+
+    pub fn marker_path(&self, env: &DiscoveryEnv) -> PathBuf {
+        (self.marker)(env)
+    }
+}
+
+pub const KNOWN_LOCATIONS: &[KnownLocation] = &[
+    KnownLocation {
+        name: "claude-desktop",
+        kind: AgentKind::ClaudeDesktop,
+        config: |env| {
+            env.xdg_config_home
+                .join("Claude/claude_desktop_config.json")
+        },
+        marker: |env| env.xdg_config_home.join("Claude"),
+    },
+    KnownLocation {
+        name: "claude-code",
+        kind: AgentKind::ClaudeCode,
+        config: |env| env.home.join(".claude.json"),
+        marker: |env| env.home.join(".claude"),
+    },
+    KnownLocation {
+        name: "cursor",
+        kind: AgentKind::Cursor,
+        config: |env| env.home.join(".cursor/mcp.json"),
+        marker: |env| env.home.join(".cursor"),
+    },
+    KnownLocation {
+        name: "windsurf",
+        kind: AgentKind::Windsurf,
+        config: |env| env.home.join(".codeium/windsurf/mcp_config.json"),
+        marker: |env| env.home.join(".codeium/windsurf"),
+    },
+    KnownLocation {
+        name: "gemini-cli",
+        kind: AgentKind::GeminiCli,
+        config: |env| env.home.join(".gemini/settings.json"),
+        marker: |env| env.home.join(".gemini"),
+    },
+    KnownLocation {
+        name: "vscode",
+        kind: AgentKind::VsCode,
+        // 사용자 `mcp.json` 이 있으면 그 파일을, 없으면 기존 `settings.json` 의 `mcp.servers` 를 씁니다.
+        config: |env| {
+            let user = env.xdg_config_home.join("Code/User");
+            let mcp = user.join("mcp.json");
+            let settings = user.join("settings.json");
+            if !is_file(&mcp) && is_file(&settings) {
+                settings
+            } else {
+                mcp
+            }
+        },
+        marker: |env| env.xdg_config_home.join("Code/User"),
+    },
+    KnownLocation {
+        name: "codex",
+        kind: AgentKind::Codex,
+        config: |env| env.home.join(".codex/config.toml"),
+        marker: |env| env.home.join(".codex"),
+    },
+    KnownLocation {
+        name: "continue",
+        kind: AgentKind::Continue,
+        config: |env| env.home.join(".continue/config.yaml"),
+        marker: |env| env.home.join(".continue"),
+    },
+    KnownLocation {
+        name: "zed",
+        kind: AgentKind::Zed,
+        config: |env| env.xdg_config_home.join("zed/settings.json"),
+        marker: |env| env.xdg_config_home.join("zed"),
+    },
+];
+
+/// 프로젝트 디렉터리 안에 두는 에이전트 설정 위치. 마스터의 `project_overrides` 에
+/// 등장하는 프로젝트마다 `<에이전트>:<프로젝트>` 이름의 별도 대상으로 등록됩니다.
+pub struct ProjectLocation {
+    pub agent: &'static str,
+    pub kind: AgentKind,
+    pub relative_path: &'static str,
+}
+
+pub const PROJECT_LOCATIONS: &[ProjectLocation] = &[
+    ProjectLocation {
+        agent: "cursor",
+        kind: AgentKind::Cursor,
+        relative_path: ".cursor/mcp.json",
+    },
+    ProjectLocation {
+        agent: "vscode",
+        kind: AgentKind::VsCode,
+        relative_path: ".vscode/mcp.json",
+    },
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredTool {
+    pub name: String,
+    pub agent: String,
+    pub kind: AgentKind,
+    pub config_path: PathBuf,
+    /// 프로젝트 단위 설정이면 해당 프로젝트
+    pub project: Option<String>,
+}
+
+/// 설치 흔적이 있는 에이전트만 반환합니다. 파일을 읽지는 않습니다.
+pub fn discover_installed(env: &DiscoveryEnv) -> Vec<DiscoveredTool> {
+    KNOWN_LOCATIONS
+        .iter()
+        .filter_map(|location| {
+            let config_path = location.config_path(env);
+            if is_file(&config_path) || location.marker_path(env).is_dir() {
+                Some(DiscoveredTool {
+                    name: location.name.to_string(),
+                    agent: location.name.to_string(),
+                    kind: location.kind,
+                    config_path,
+                    project: None,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// `projects` 중 디렉터리로 존재하는 프로젝트에 대해 프로젝트 단위 설정 위치를 반환합니다.
+/// 프로젝트 설정 파일이나 에이전트 디렉터리가 이미 있거나, 에이전트가 전역으로 설치된
+/// 경우에만 등록하며 파일이 없으면 첫 동기화 때 생성합니다.
+pub fn discover_projects(env: Option<&DiscoveryEnv>, projects: &[&str]) -> Vec<DiscoveredTool> {
+    let installed: Vec<_> = env.map(discover_installed).unwrap_or_default();
+    let mut found = Vec::new();
+    for project in projects {
+        let Some(dir) = resolve_project_dir(env, project) else {
+            continue;
+        };
+        for location in PROJECT_LOCATIONS {
+            let config_path = dir.join(location.relative_path);
+            let agent_dir = config_path.parent().filter(|parent| *parent != dir);
+            let used = is_file(&config_path)
+                || agent_dir.is_some_and(Path::is_dir)
+                || installed.iter().any(|tool| tool.agent == location.agent);
+            if used {
+                found.push(DiscoveredTool {
+                    name: format!("{}:{}", location.agent, project),
+                    agent: location.agent.to_string(),
+                    kind: location.kind,
+                    config_path,
+                    project: Some(project.to_string()),
+                });
+            }
+        }
+    }
+    found
+}
+
+/// 절대 경로나 `~/` 로 시작하는 프로젝트 값만 디렉터리로 해석합니다.
+pub fn resolve_project_dir(env: Option<&DiscoveryEnv>, project: &str) -> Option<PathBuf> {
+    let dir = match project.strip_prefix("~/") {
+        Some(rest) => env?.home.join(rest),
+        None if Path::new(project).is_absolute() => PathBuf::from(project),
+        None => return None,
+    };
+    dir.is_dir().then_some(dir)
+}
+
+fn is_file(path: &Path) -> bool {
+    path.metadata()
+        .map(|metadata| metadata.is_file())
+        .unwrap_or(false)
+}
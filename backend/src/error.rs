@@ -11,10 +11,14 @@ pub enum BackendError {
     Io(#[from] std::io::Error),
     #[error("serialization error: {0}")]
     SerdeJson(#[from] serde_json::Error),
+    #[error("invalid tool configuration: {0}")]
+    InvalidConfig(String),
     #[error("configuration not found")]
     ConfigNotFound,
     #[error("not found: {0}")]
     NotFound(String),
+    #[error("forbidden: {0}")]
+    Forbidden(String),
     #[error("other error: {0}")]
     Other(String),
 }
@@ -30,8 +34,10 @@ impl IntoResponse for BackendError {
             BackendError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
             BackendError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
             BackendError::SerdeJson(_) => StatusCode::BAD_REQUEST,
+            BackendError::InvalidConfig(_) => StatusCode::BAD_REQUEST,
             BackendError::ConfigNotFound => StatusCode::NOT_FOUND,
             BackendError::NotFound(_) => StatusCode::NOT_FOUND,
+            BackendError::Forbidden(_) => StatusCode::FORBIDDEN,
             BackendError::Other(_) => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
@@ -0,0 +1,179 @@
+use serde_json::Value;
+
+use crate::config::{McpServer, McpSettings};
+use crate::error::{BackendError, BackendResult};
+use crate::secrets::map_server_strings;
+
+const REFERENCE_PREFIX: &str = "${env:";
+const DEFAULT_SEPARATOR: &str = ":-";
+
+/// 서버 정의의 `${env:이름}`, `${env:이름:-기본값}` 참조를 현재 프로세스의 환경 변수로 풉니다.
+pub fn expand(settings: &McpSettings) -> BackendResult<McpSettings> {
+    expand_with(settings, |name| std::env::var(name).ok())
+}
+
+/// `expand` 와 같지만 환경 변수 대신 `lookup` 으로 값을 찾습니다.
+pub fn expand_with(
+    settings: &McpSettings,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> BackendResult<McpSettings> {
+    map_server_strings(settings, |text| expand_text(text, &lookup))
+}
+
+/// 도구 파일에서 읽은 값 중 마스터 참조를 푼 결과와 같은 값을 다시 참조로 되돌립니다.
+/// 비교는 항상 풀기 전의 형태로 하므로 환경 변수 값 때문에 차이가 생기지 않습니다.
+pub fn unexpand(tool: &McpSettings, master: &McpSettings) -> McpSettings {
+    unexpand_with(tool, master, |name| std::env::var(name).ok())
+}
+
+pub fn unexpand_with(
+    tool: &McpSettings,
+    master: &McpSettings,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> McpSettings {
+    restore_references(tool, master, |reference, text| {
+        expand_text(reference, &lookup).is_ok_and(|expanded| expanded == text)
+    })
+}
+
+/// 지난 동기화에서 풀어 기록한 값(`expanded`)과 글자까지 같은 도구 값만 기준(`base`)의 참조로 되돌립니다.
+/// 그사이 환경 변수 값이 바뀌어도 로컬에서 고치지 않은 항목은 로컬 수정으로 보지 않습니다.
+pub fn unexpand_previous(
+    tool: &McpSettings,
+    base: &McpSettings,
+    expanded: &McpSettings,
+) -> McpSettings {
+    let mut settings = tool.clone();
+    for server in &mut settings.servers {
+        let find = |settings: &McpSettings| {
+            settings
+                .servers
+                .iter()
+                .find(|item| item.id == server.id)
+                .and_then(|item| serde_json::to_value(item).ok())
+        };
+        let (Some(reference), Some(previous), Ok(mut value)) =
+            (find(base), find(expanded), serde_json::to_value(&*server))
+        else {
+            continue;
+        };
+        if restore_unchanged(&mut value, &reference, &previous) {
+            if let Ok(restored) = serde_json::from_value::<McpServer>(value) {
+                *server = restored;
+            }
+        }
+    }
+    settings
+}
+
+fn restore_unchanged(value: &mut Value, reference: &Value, previous: &Value) -> bool {
+    match (value, reference, previous) {
+        (Value::String(text), Value::String(reference), Value::String(previous))
+            if text == previous && text != reference && reference.contains(REFERENCE_PREFIX) =>
+        {
+            *text = reference.clone();
+            true
+        }
+        (Value::Object(fields), Value::Object(reference), Value::Object(previous)) => {
+            let mut restored = false;
+            for (key, field) in fields.iter_mut() {
+                if let (Some(reference), Some(previous)) = (reference.get(key), previous.get(key)) {
+                    restored |= restore_unchanged(field, reference, previous);
+                }
+            }
+            restored
+        }
+        (Value::Array(items), Value::Array(reference), Value::Array(previous)) => {
+            let mut restored = false;
+            for ((item, reference), previous) in items.iter_mut().zip(reference).zip(previous) {
+                restored |= restore_unchanged(item, reference, previous);
+            }
+            restored
+        }
+        _ => false,
+    }
+}
+
+fn restore_references(
+    tool: &McpSettings,
+    master: &McpSettings,
+    matches: impl Fn(&str, &str) -> bool,
+) -> McpSettings {
+    let mut settings = tool.clone();
+    for server in &mut settings.servers {
+        let Some(reference) = master.servers.iter().find(|item| item.id == server.id) else {
+            continue;
+        };
+        let (Ok(mut value), Ok(reference)) = (
+            serde_json::to_value(&*server),
+            serde_json::to_value(reference),
+        ) else {
+            continue;
+        };
+        if restore_value(&mut value, &reference, &matches) {
+            if let Ok(restored) = serde_json::from_value::<McpServer>(value) {
+                *server = restored;
+            }
+        }
+    }
+    settings
+}
+
+fn restore_value(
+    value: &mut Value,
+    reference: &Value,
+    matches: &impl Fn(&str, &str) -> bool,
+) -> bool {
+    match (value, reference) {
+        (Value::String(text), Value::String(reference))
+            if text != reference
+                && reference.contains(REFERENCE_PREFIX)
+                && matches(reference, text) =>
+        {
+            *text = reference.clone();
+            true
+        }
+        (Value::Object(fields), Value::Object(reference)) => {
+            let mut restored = false;
+            for (key, field) in fields.iter_mut() {
+                if let Some(reference) = reference.get(key) {
+                    restored |= restore_value(field, reference, matches);
+                }
+            }
+            restored
+        }
+        (Value::Array(items), Value::Array(reference)) => {
+            let mut restored = false;
+            for (item, reference) in items.iter_mut().zip(reference) {
+                restored |= restore_value(item, reference, matches);
+            }
+            restored
+        }
+        _ => false,
+    }
+}
+
+/// 비어 있거나 없는 변수는 기본값을 쓰고, 기본값도 없으면 오류입니다.
+fn expand_text(text: &str, lookup: &impl Fn(&str) -> Option<String>) -> BackendResult<String> {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(REFERENCE_PREFIX) {
+        let after = &rest[start + REFERENCE_PREFIX.len()..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let (name, default) = match after[..end].split_once(DEFAULT_SEPARATOR) {
+            Some((name, default)) => (name, Some(default)),
+            None => (&after[..end], None),
+        };
+        let value = lookup(name)
+            .filter(|value| !value.is_empty())
+            .or_else(|| default.map(str::to_string))
+            .ok_or_else(|| BackendError::NotFound(format!("environment variable '{name}'")))?;
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
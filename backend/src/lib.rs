@@ -1,5 +1,13 @@
+pub mod adapter;
 pub mod api;
+pub mod audit;
 pub mod config;
 pub mod db;
+pub mod diff;
+pub mod discovery;
 pub mod error;
+pub mod interpolate;
+pub mod redact;
+pub mod secrets;
+pub mod settings;
 pub mod sync;
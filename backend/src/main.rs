@@ -1,26 +1,50 @@
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use axum::Router;
 use backend::api::{router, AppState};
 use backend::db::Database;
 use backend::error::BackendError;
+use backend::settings::{Settings, SettingsOverrides};
 use backend::sync;
+use clap::Parser;
 use tokio::net::TcpListener;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
+#[derive(Parser)]
+#[command(name = "backend", about = "MCP Sync 백엔드 서버", version)]
+struct Args {
+    /// SQLite 데이터베이스 파일 경로
+    #[arg(long, value_name = "PATH")]
+    db: Option<PathBuf>,
+    /// `<DIR>/<도구 이름>/mcp.json` 형태의 도구 설정을 추가로 검색할 디렉터리 (기본: 있으면 ../tool_configs)
+    #[arg(long, value_name = "DIR")]
+    config_root: Option<PathBuf>,
+}
+
 #[tokio::main]
 async fn main() -> Result<(), BackendError> {
+    let args = Args::parse();
     init_tracing();
 
-    let db = Arc::new(Database::initialize()?);
+    let settings = Arc::new(Settings::load(SettingsOverrides {
+        db_path: args.db,
+        config_root: args.config_root,
+    })?);
+    info!(db = %settings.db_path.display(), "Using database");
+
+    let db = Arc::new(Database::initialize(&settings.db_path)?);
     db.ensure_master_config()?;
-    if let Err(err) = sync::discover_tools(&db) {
+    if let Err(err) = sync::discover_tools(&db, &settings) {
         tracing::warn!("failed to perform initial tool discovery: {err}");
     }
 
-    let app_state = AppState { db: db.clone() };
+    let app_state = AppState {
+        db: db.clone(),
+        settings,
+    };
     let app: Router = router(app_state);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 8080));
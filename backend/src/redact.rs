@@ -0,0 +1,328 @@
+use serde_json::Value;
+
+use crate::config::{
+    MasterConfigResponse, MasterRevision, McpServer, McpSettings, Profile, SyncConflict, SyncPlan,
+    ToolConfiguration,
+};
+use crate::diff::{FieldChange, ServerChange, SettingsDiff, ToolDiff};
+
+const MASK: &str = "****";
+/// 이름에 이 단어가 들어간 키(`GITHUB_TOKEN`, `Authorization` 등)의 값은 자격 증명으로 봅니다.
+const SENSITIVE_WORDS: &[&str] = &[
+    "key",
+    "token",
+    "secret",
+    "password",
+    "passwd",
+    "auth",
+    "credential",
+    "cookie",
+    "session",
+];
+/// 키 이름과 관계없이 자격 증명으로 볼 값의 접두사
+const TOKEN_PREFIXES: &[&str] = &[
+    "sk-",
+    "ghp_",
+    "gho_",
+    "ghu_",
+    "ghs_",
+    "github_pat_",
+    "glpat-",
+    "xoxb-",
+    "xoxp-",
+    "AKIA",
+    "Bearer ",
+];
+
+/// API 응답과 CLI 출력에서 자격 증명을 가립니다. `${secret:이름}` 같은 참조는 그대로 둡니다.
+pub trait Redact {
+    fn redact(&mut self);
+
+    fn redacted(mut self) -> Self
+    where
+        Self: Sized,
+    {
+        self.redact();
+        self
+    }
+}
+
+/// 앞 네 글자만 남긴 미리보기. 짧은 값은 모두 가립니다.
+pub fn mask(value: &str) -> String {
+    if value.chars().count() >= 12 {
+        let prefix: String = value.chars().take(4).collect();
+        format!("{prefix}{MASK}")
+    } else {
+        MASK.to_string()
+    }
+}
+
+pub fn is_sensitive_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SENSITIVE_WORDS.iter().any(|word| name.contains(word))
+}
+
+pub fn looks_like_token(value: &str) -> bool {
+    TOKEN_PREFIXES
+        .iter()
+        .any(|prefix| value.starts_with(prefix) && value.len() > prefix.len() + 8)
+}
+
+/// 참조(`${secret:...}`, `${env:...}`)를 담은 값은 원문이 아니므로 가리지 않습니다.
+pub fn is_reference(value: &str) -> bool {
+    value.contains("${")
+}
+
+/// 오류 메시지 같은 자유 형식 글에서 토큰처럼 보이는 단어를 가립니다.
+pub fn redact_text(text: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(is_word) {
+        let end = rest[start..]
+            .find(|c: char| !is_word(c))
+            .map_or(rest.len(), |len| start + len);
+        let word = &rest[start..end];
+        redacted.push_str(&rest[..start]);
+        if looks_like_token(word) {
+            redacted.push_str(&mask(word));
+        } else {
+            redacted.push_str(word);
+        }
+        rest = &rest[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+/// 서버 정의에서 가려야 할 원문 값을 모읍니다.
+pub fn sensitive_values(settings: &McpSettings) -> Vec<String> {
+    let mut found = Vec::new();
+    for server in &settings.servers {
+        if let Ok(mut value) = serde_json::to_value(server) {
+            redact_value("", &mut value, &mut found);
+        }
+    }
+    found
+}
+
+/// 가린 값을 그대로 돌려보낸 경우(조회 → 수정 → 저장) `current` 의 원래 값으로 되돌립니다.
+pub fn restore_masked(incoming: &mut McpSettings, current: &McpSettings) {
+    for server in &mut incoming.servers {
+        let Some(original) = current.servers.iter().find(|item| item.id == server.id) else {
+            continue;
+        };
+        let (Ok(mut value), Ok(original)) = (
+            serde_json::to_value(&*server),
+            serde_json::to_value(original),
+        ) else {
+            continue;
+        };
+        if restore_value(&mut value, &original) {
+            if let Ok(restored) = serde_json::from_value::<McpServer>(value) {
+                *server = restored;
+            }
+        }
+    }
+}
+
+fn restore_value(value: &mut Value, original: &Value) -> bool {
+    match (value, original) {
+        (Value::String(text), Value::String(original))
+            if text != original && *text == mask(original) =>
+        {
+            *text = original.clone();
+            true
+        }
+        (Value::Object(fields), Value::Object(original)) => {
+            let mut restored = false;
+            for (key, field) in fields.iter_mut() {
+                if let Some(original) = original.get(key) {
+                    restored |= restore_value(field, original);
+                }
+            }
+            restored
+        }
+        (Value::Array(items), Value::Array(original)) => {
+            let mut restored = false;
+            for (item, original) in items.iter_mut().zip(original) {
+                restored |= restore_value(item, original);
+            }
+            restored
+        }
+        _ => false,
+    }
+}
+
+/// `key` 는 값이 들어 있는 필드 이름입니다. 자격 증명 키 아래의 문자열은 모두 가리고,
+/// `args` 는 `--api-key VALUE`, `--token=VALUE` 형태의 값만 가립니다.
+fn redact_value(key: &str, value: &mut Value, found: &mut Vec<String>) {
+    if is_sensitive_name(key) {
+        mask_strings(value, found);
+        return;
+    }
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                redact_value(key, field, found);
+            }
+        }
+        Value::Array(items) if key == "args" => redact_args(items, found),
+        Value::Array(items) => {
+            for item in items {
+                redact_value(key, item, found);
+            }
+        }
+        Value::String(text) if looks_like_token(text) => mask_string(text, found),
+        _ => {}
+    }
+}
+
+fn redact_args(items: &mut [Value], found: &mut Vec<String>) {
+    let mut flagged = false;
+    let mut header = false;
+    for item in items {
+        let Value::String(arg) = item else {
+            flagged = false;
+            header = false;
+            continue;
+        };
+        if flagged && !arg.starts_with('-') {
+            mask_string(arg, found);
+            flagged = false;
+            continue;
+        }
+        flagged = false;
+        // `--header 이름:값` 은 이름이 민감하면 값만 가립니다.
+        if header {
+            header = false;
+            if let Some((name, value)) = arg.split_once(':') {
+                let value = value.trim_start();
+                if is_sensitive_name(name) && !value.is_empty() && !is_reference(value) {
+                    found.push(value.to_string());
+                    *arg = format!("{name}:{}", mask(value));
+                }
+                continue;
+            }
+        }
+        if arg == "--header" || arg == "-H" {
+            header = true;
+            continue;
+        }
+        if let Some(flag) = arg.strip_prefix('-').filter(|flag| is_sensitive_name(flag)) {
+            match flag.split_once('=') {
+                Some((name, secret)) if !secret.is_empty() && !is_reference(secret) => {
+                    found.push(secret.to_string());
+                    *arg = format!("-{name}={}", mask(secret));
+                }
+                Some(_) => {}
+                None => flagged = true,
+            }
+        } else if looks_like_token(arg) {
+            mask_string(arg, found);
+        }
+    }
+}
+
+fn mask_strings(value: &mut Value, found: &mut Vec<String>) {
+    match value {
+        Value::String(text) => mask_string(text, found),
+        Value::Array(items) => items.iter_mut().for_each(|item| mask_strings(item, found)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|field| mask_strings(field, found)),
+        _ => {}
+    }
+}
+
+fn mask_string(text: &mut String, found: &mut Vec<String>) {
+    if text.is_empty() || is_reference(text) {
+        return;
+    }
+    found.push(text.clone());
+    *text = mask(text);
+}
+
+impl Redact for McpServer {
+    fn redact(&mut self) {
+        if let Ok(mut value) = serde_json::to_value(&*self) {
+            redact_value("", &mut value, &mut Vec::new());
+            if let Ok(redacted) = serde_json::from_value(value) {
+                *self = redacted;
+            }
+        }
+    }
+}
+
+impl Redact for McpSettings {
+    fn redact(&mut self) {
+        self.servers.iter_mut().for_each(Redact::redact);
+    }
+}
+
+impl Redact for MasterConfigResponse {
+    fn redact(&mut self) {
+        self.settings.redact();
+    }
+}
+
+impl Redact for MasterRevision {
+    fn redact(&mut self) {
+        self.settings.redact();
+    }
+}
+
+impl Redact for Profile {
+    fn redact(&mut self) {
+        self.settings.redact();
+    }
+}
+
+impl Redact for ToolConfiguration {
+    fn redact(&mut self) {
+        self.settings.redact();
+    }
+}
+
+impl Redact for FieldChange {
+    fn redact(&mut self) {
+        redact_value(&self.field, &mut self.master, &mut Vec::new());
+        redact_value(&self.field, &mut self.tool, &mut Vec::new());
+    }
+}
+
+impl Redact for SettingsDiff {
+    fn redact(&mut self) {
+        for change in &mut self.servers {
+            match change {
+                ServerChange::Added { server, .. } | ServerChange::Removed { server, .. } => {
+                    server.redact()
+                }
+                ServerChange::Modified { fields, .. } => fields.iter_mut().for_each(Redact::redact),
+            }
+        }
+    }
+}
+
+impl Redact for ToolDiff {
+    fn redact(&mut self) {
+        self.changes.redact();
+    }
+}
+
+impl Redact for SyncConflict {
+    fn redact(&mut self) {
+        let field = self.field.as_deref().unwrap_or_default();
+        redact_value(field, &mut self.master, &mut Vec::new());
+        redact_value(field, &mut self.tool, &mut Vec::new());
+    }
+}
+
+impl Redact for SyncPlan {
+    /// 기록될 파일 내용은 계획을 만들 때 가린 구성으로 렌더링해 둔 내용으로 바꿉니다.
+    fn redact(&mut self) {
+        self.proposed = std::mem::take(&mut self.proposed_redacted);
+        self.changes.redact();
+        self.conflicts.iter_mut().for_each(Redact::redact);
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde_json::Value;
+
+use crate::config::{McpServer, McpSettings};
+use crate::error::{BackendError, BackendResult};
+
+const REFERENCE_PREFIX: &str = "${secret:";
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+/// 문자열 일부로 들어 있을 때도 참조로 되돌리는 비밀 값의 최소 길이
+const MIN_EMBEDDED_LEN: usize = 8;
+
+/// 비밀 저장소를 암호화하는 로컬 키. 데이터베이스 옆의 `0600` 파일에 보관합니다.
+pub struct SecretKey(ChaCha20Poly1305);
+
+impl SecretKey {
+    /// 키 파일이 있으면 읽고, 없으면 새 키를 만들어 소유자만 읽을 수 있게 저장합니다.
+    pub fn load_or_create(path: &Path) -> BackendResult<Self> {
+        match fs::read(path) {
+            Ok(bytes) => Self::from_bytes(&bytes, path),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let mut options = OpenOptions::new();
+                options.write(true).create_new(true);
+                #[cfg(unix)]
+                options.mode(0o600);
+                let mut file = options.open(path)?;
+                file.write_all(&key)?;
+                file.sync_all()?;
+                Ok(Self(ChaCha20Poly1305::new(&key)))
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8], path: &Path) -> BackendResult<Self> {
+        if bytes.len() != KEY_LEN {
+            return Err(BackendError::Other(format!(
+                "secret key file {} is corrupted",
+                path.display()
+            )));
+        }
+        Ok(Self(ChaCha20Poly1305::new(Key::from_slice(bytes))))
+    }
+
+    /// 무작위 nonce 를 앞에 붙인 암호문을 반환합니다.
+    pub fn encrypt(&self, plaintext: &str) -> BackendResult<Vec<u8>> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .0
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| BackendError::Other("failed to encrypt secret".to_string()))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        Ok(sealed)
+    }
+
+    pub fn decrypt(&self, sealed: &[u8]) -> BackendResult<String> {
+        let invalid = || BackendError::Other("failed to decrypt secret (wrong key?)".to_string());
+        if sealed.len() < NONCE_LEN {
+            return Err(invalid());
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let plaintext = self
+            .0
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| invalid())?;
+        String::from_utf8(plaintext).map_err(|_| invalid())
+    }
+}
+
+/// `${secret:이름}` 참조에 쓸 수 있는 이름인지 확인합니다.
+pub fn validate_name(name: &str) -> BackendResult<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(BackendError::InvalidConfig(format!(
+            "invalid secret name '{name}' (use letters, digits, '_', '-' or '.')"
+        )))
+    }
+}
+
+pub fn reference(name: &str) -> String {
+    format!("{REFERENCE_PREFIX}{name}}}")
+}
+
+/// 복호화한 비밀 값. 마스터의 `${secret:이름}` 참조는 파일에 기록할 때만 값으로 바꾸고,
+/// 도구 파일에서 읽은 값은 다시 참조로 바꿔 비교하므로 데이터베이스에는 원문이 남지 않습니다.
+#[derive(Debug, Clone, Default)]
+pub struct Secrets {
+    values: BTreeMap<String, String>,
+}
+
+impl Secrets {
+    pub fn new(values: BTreeMap<String, String>) -> Self {
+        Self { values }
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    /// 서버 정의의 모든 문자열에서 참조를 값으로 바꿉니다. 없는 비밀을 참조하면 오류입니다.
+    pub fn resolve(&self, settings: &McpSettings) -> BackendResult<McpSettings> {
+        map_server_strings(settings, |text| self.resolve_text(text))
+    }
+
+    /// `resolve` 의 반대. 저장된 비밀 값과 같은 문자열을 참조로 되돌립니다. 문자열 일부는
+    /// `MIN_EMBEDDED_LEN` 이상인 값이 단어 경계에 있을 때만 바꿔(`Bearer <값>`, `--token=<값>` 등),
+    /// 짧은 비밀이 명령이나 인자 속의 관계없는 글자를 바꾸지 않게 합니다.
+    pub fn conceal(&self, settings: &McpSettings) -> McpSettings {
+        if self.values.is_empty() {
+            return settings.clone();
+        }
+        let values = self.sorted_values();
+        map_server_strings(settings, |text| Ok(conceal_with(&values, text)))
+            .unwrap_or_else(|_| settings.clone())
+    }
+
+    /// 오류 메시지 같은 자유 형식 글에 들어간 비밀 값을 `conceal` 과 같은 규칙으로 참조로 바꿉니다.
+    pub fn conceal_text(&self, text: &str) -> String {
+        conceal_with(&self.sorted_values(), text)
+    }
+
+    /// 다른 값을 포함하는 긴 값부터 바꾸도록 길이 역순으로 정렬합니다.
+    fn sorted_values(&self) -> Vec<(&String, &String)> {
+        let mut values: Vec<_> = self
+            .values
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .collect();
+        values.sort_by_key(|(_, value)| std::cmp::Reverse(value.len()));
+        values
+    }
+
+    fn resolve_text(&self, text: &str) -> BackendResult<String> {
+        let mut resolved = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find(REFERENCE_PREFIX) {
+            let after = &rest[start + REFERENCE_PREFIX.len()..];
+            let Some(end) = after.find('}') else {
+                break;
+            };
+            let name = &after[..end];
+            let value = self
+                .values
+                .get(name)
+                .ok_or_else(|| BackendError::NotFound(format!("secret '{name}'")))?;
+            resolved.push_str(&rest[..start]);
+            resolved.push_str(value);
+            rest = &after[end + 1..];
+        }
+        resolved.push_str(rest);
+        Ok(resolved)
+    }
+}
+
+fn conceal_with(values: &[(&String, &String)], text: &str) -> String {
+    if let Some((name, _)) = values.iter().find(|(_, value)| value.as_str() == text) {
+        return reference(name);
+    }
+    let mut concealed = text.to_string();
+    for (name, value) in values {
+        if value.len() >= MIN_EMBEDDED_LEN && concealed.contains(value.as_str()) {
+            concealed = replace_bounded(&concealed, value, &reference(name));
+        }
+    }
+    concealed
+}
+
+/// 앞뒤가 단어 문자(영숫자, `_`, `-`)가 아닌 곳의 `needle` 만 바꿉니다.
+fn replace_bounded(text: &str, needle: &str, with: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    let mut replaced = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(needle) {
+        let end = start + needle.len();
+        let before = rest[..start]
+            .chars()
+            .next_back()
+            .or(replaced.chars().next_back());
+        let after = rest[end..].chars().next();
+        replaced.push_str(&rest[..start]);
+        if before.is_some_and(is_word) || after.is_some_and(is_word) {
+            replaced.push_str(needle);
+        } else {
+            replaced.push_str(with);
+        }
+        rest = &rest[end..];
+    }
+    replaced.push_str(rest);
+    replaced
+}
+
+/// 설정 안의 `${secret:이름}` 참조 이름을 모읍니다.
+pub fn referenced_names(settings: &McpSettings) -> Vec<String> {
+    let mut names = Vec::new();
+    let _ = map_server_strings(settings, |text| {
+        let mut rest = text;
+        while let Some(start) = rest.find(REFERENCE_PREFIX) {
+            let after = &rest[start + REFERENCE_PREFIX.len()..];
+            let Some(end) = after.find('}') else {
+                break;
+            };
+            if !names.iter().any(|name| name == &after[..end]) {
+                names.push(after[..end].to_string());
+            }
+            rest = &after[end + 1..];
+        }
+        Ok(text.to_string())
+    });
+    names
+}
+
+/// 서버 정의(에이전트 고유 키 포함)의 문자열 값에 `f` 를 적용합니다. 키와 서버 ID 는 바꾸지 않습니다.
+pub(crate) fn map_server_strings(
+    settings: &McpSettings,
+    mut f: impl FnMut(&str) -> BackendResult<String>,
+) -> BackendResult<McpSettings> {
+    let mut mapped = settings.clone();
+    for server in &mut mapped.servers {
+        let mut value = serde_json::to_value(&*server)?;
+        if let Value::Object(fields) = &mut value {
+            for (key, field) in fields.iter_mut() {
+                if key != "id" {
+                    map_strings(field, &mut f)?;
+                }
+            }
+        }
+        *server = serde_json::from_value::<McpServer>(value)?;
+    }
+    Ok(mapped)
+}
+
+fn map_strings(
+    value: &mut Value,
+    f: &mut impl FnMut(&str) -> BackendResult<String>,
+) -> BackendResult<()> {
+    match value {
+        Value::String(text) => *text = f(text)?,
+        Value::Array(items) => {
+            for item in items {
+                map_strings(item, f)?;
+            }
+        }
+        Value::Object(fields) => {
+            for field in fields.values_mut() {
+                map_strings(field, f)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::discovery::DiscoveryEnv;
+use crate::error::{BackendError, BackendResult};
+
+const APP_DIR: &str = "ai-mcp-sync";
+const CONFIG_FILE_NAME: &str = "config.toml";
+const DB_FILE_NAME: &str = "mcp_sync.db";
+pub const DB_ENV: &str = "MCP_SYNC_DB";
+pub const CONFIG_ROOT_ENV: &str = "MCP_SYNC_CONFIG_ROOT";
+pub const ADMIN_TOKEN_ENV: &str = "MCP_SYNC_ADMIN_TOKEN";
+/// 설정 계층이 생기기 전의 기본 위치. `backend/` 에서 실행하던 작업 디렉터리 기준입니다.
+const LEGACY_DB_PATH: &str = "../database/mcp_sync.db";
+/// 설정 계층이 생기기 전의 기본 도구 설정 디렉터리. 존재할 때만 검색합니다.
+const LEGACY_CONFIG_ROOT: &str = "../tool_configs";
+
+/// 데이터베이스 위치와 도구 설정 검색 범위. 우선순위는 명령행 인자 > 환경 변수 > 설정 파일 > 기본값입니다.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub db_path: PathBuf,
+    /// `<root>/<도구 이름>/mcp.json` 구조로 도구 설정을 추가 검색할 디렉터리. 지정하지 않으면 `../tool_configs` 가 있을 때 씁니다.
+    pub config_root: Option<PathBuf>,
+    /// 홈 디렉터리의 알려진 설정 위치 검색 기준. `None` 이면 검색하지 않습니다.
+    pub discovery: Option<DiscoveryEnv>,
+    /// API 에서 `?reveal=true` 로 가리지 않은 자격 증명을 요청할 때 필요한 Bearer 토큰.
+    /// 설정하지 않으면 API 는 항상 가린 값만 반환합니다.
+    pub admin_token: Option<String>,
+}
+
+/// 명령행 인자로 받은 값. 지정하지 않은 항목은 `None` 입니다.
+#[derive(Debug, Clone, Default)]
+pub struct SettingsOverrides {
+    pub db_path: Option<PathBuf>,
+    pub config_root: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SettingsFile {
+    db_path: Option<PathBuf>,
+    config_root: Option<PathBuf>,
+    admin_token: Option<String>,
+}
+
+impl Settings {
+    pub fn load(overrides: SettingsOverrides) -> BackendResult<Self> {
+        let discovery = DiscoveryEnv::from_env();
+        let (file, file_dir) = match config_file_path(discovery.as_ref()) {
+            Some(path) if path.is_file() => {
+                let content = fs::read_to_string(&path)?;
+                let parsed: SettingsFile = toml::from_str(&content).map_err(|err| {
+                    BackendError::Other(format!("failed to parse {}: {err}", path.display()))
+                })?;
+                (parsed, path.parent().map(Path::to_path_buf))
+            }
+            _ => (SettingsFile::default(), None),
+        };
+        // 설정 파일 안의 상대 경로는 설정 파일이 있는 디렉터리를 기준으로 해석합니다.
+        let from_file = |value: Option<PathBuf>| {
+            value.map(|path| match &file_dir {
+                Some(dir) if path.is_relative() => dir.join(path),
+                _ => path,
+            })
+        };
+
+        let db_path = overrides
+            .db_path
+            .or_else(|| env_path(DB_ENV))
+            .or_else(|| from_file(file.db_path))
+            .unwrap_or_else(|| {
+                let path = default_db_path(discovery.as_ref());
+                import_legacy_db(Path::new(LEGACY_DB_PATH), &path);
+                path
+            });
+        let config_root = overrides
+            .config_root
+            .or_else(|| env_path(CONFIG_ROOT_ENV))
+            .or_else(|| from_file(file.config_root))
+            .or_else(|| {
+                let legacy = Path::new(LEGACY_CONFIG_ROOT);
+                legacy.is_dir().then(|| legacy.to_path_buf())
+            });
+        let admin_token = env::var(ADMIN_TOKEN_ENV)
+            .ok()
+            .or(file.admin_token)
+            .filter(|token| !token.is_empty());
+
+        Ok(Self {
+            db_path,
+            config_root,
+            discovery,
+            admin_token,
+        })
+    }
+}
+
+impl Settings {
+    /// API 의 `?reveal=true` 와 CLI 의 `--reveal` 이 함께 쓰는 관리자 토큰 확인입니다.
+    pub fn authorize_reveal(&self, provided: Option<&str>) -> BackendResult<()> {
+        let expected = self.admin_token.as_deref().ok_or_else(|| {
+            BackendError::Forbidden("reveal requires an admin token to be configured".to_string())
+        })?;
+        match provided {
+            Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => Ok(()),
+            _ => Err(BackendError::Forbidden("invalid admin token".to_string())),
+        }
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// `$XDG_CONFIG_HOME/ai-mcp-sync/config.toml`
+pub fn config_file_path(discovery: Option<&DiscoveryEnv>) -> Option<PathBuf> {
+    discovery.map(|env| env.xdg_config_home.join(APP_DIR).join(CONFIG_FILE_NAME))
+}
+
+/// `$XDG_DATA_HOME/ai-mcp-sync/mcp_sync.db` (기본 `~/.local/share`)
+fn default_db_path(discovery: Option<&DiscoveryEnv>) -> PathBuf {
+    let data_home =
+        env_path("XDG_DATA_HOME").or_else(|| discovery.map(|env| env.home.join(".local/share")));
+    match data_home {
+        Some(dir) => dir.join(APP_DIR).join(DB_FILE_NAME),
+        None => PathBuf::from(DB_FILE_NAME),
+    }
+}
+
+/// 기본 위치에 데이터베이스가 없고 예전 기본 위치에 있으면 복사해 옵니다. 예전 파일은 그대로 둡니다.
+fn import_legacy_db(legacy: &Path, target: &Path) {
+    if target.exists() || !legacy.is_file() {
+        return;
+    }
+    let copied = target
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::copy(legacy, target));
+    match copied {
+        Ok(_) => tracing::warn!(
+            "imported the database from the legacy location {} into {}",
+            legacy.display(),
+            target.display()
+        ),
+        Err(err) => tracing::warn!(
+            "failed to import the legacy database {}: {err}; set `db_path` in {} or {} to keep using it",
+            legacy.display(),
+            CONFIG_FILE_NAME,
+            DB_ENV
+        ),
+    }
+}
+
+fn env_path(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
@@ -1,96 +1,696 @@
 use std::collections::HashSet;
-use std::fs;
-use std::path::Path;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use chrono::Utc;
+use serde_json::{Map, Value};
 use walkdir::WalkDir;
 
-use crate::config::{McpSettings, ProjectOverride, SyncStatus, SyncSummary, ToolConfiguration};
-use crate::db::Database;
-use crate::error::BackendResult;
+use crate::adapter::AgentKind;
+use crate::config::{
+    ConflictSide, EnvMode, McpServer, McpSettings, McpTransport, ProjectOverride, SyncConflict,
+    SyncPlan, SyncPolicy, SyncStatus, SyncSummary, ToolConfiguration,
+};
+use crate::db::{Database, SyncBase, ToolRecord};
+use crate::diff::{self, SettingsDiff};
+use crate::discovery;
+use crate::error::{BackendError, BackendResult};
+use crate::interpolate;
+use crate::redact::{self, Redact};
+use crate::secrets::Secrets;
+use crate::settings::Settings;
 
-const TOOL_CONFIG_DIR: &str = "../tool_configs";
 const MCP_FILE_NAME: &str = "mcp.json";
 
-pub fn discover_tools(db: &Database) -> BackendResult<Vec<ToolConfiguration>> {
-    let mut tools = Vec::new();
-    let root = Path::new(TOOL_CONFIG_DIR);
-    if !root.exists() {
-        return Ok(Vec::new());
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// `config_root` 디렉터리와 홈 디렉터리의 알려진 설정 위치, 마스터 `project_overrides` 의
+/// 프로젝트 디렉터리를 검색해 도구로 등록합니다.
+pub fn discover_tools(db: &Database, settings: &Settings) -> BackendResult<Vec<ToolConfiguration>> {
+    let discovered = match &settings.discovery {
+        Some(env) => discovery::discover_installed(env),
+        None => Vec::new(),
+    };
+    let profiles = db.list_profiles()?;
+    let mut projects: Vec<&str> = Vec::new();
+    for entry in profiles
+        .iter()
+        .flat_map(|profile| &profile.settings.project_overrides)
+    {
+        if !projects.contains(&entry.project.as_str()) {
+            projects.push(&entry.project);
+        }
     }
-    for entry in WalkDir::new(root)
-        .max_depth(2)
+    let project_tools = discovery::discover_projects(settings.discovery.as_ref(), &projects);
+    let mut found: Vec<ToolRecord> = discovered
         .into_iter()
-        .filter_map(Result::ok)
-    {
-        if entry.file_type().is_file() && entry.file_name() == MCP_FILE_NAME {
-            let path = entry.into_path();
-            let tool_name = path
-                .parent()
-                .and_then(|p| p.file_name())
-                .and_then(|os| os.to_str())
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| "unknown".to_string());
-            let settings = read_settings_from_file(&path)?;
-            let config =
-                ToolConfiguration::new(tool_name.clone(), path.to_string_lossy(), settings);
-            db.record_tool_detection(&tool_name, &path)?;
-            tools.push(config);
+        .chain(project_tools)
+        .map(|tool| ToolRecord {
+            agent: tool.agent,
+            kind: Some(tool.kind),
+            project: tool.project,
+            ..ToolRecord::new(tool.name, tool.config_path)
+        })
+        .collect();
+
+    if let Some(root) = settings.config_root.as_deref().filter(|root| root.exists()) {
+        for entry in WalkDir::new(root)
+            .max_depth(2)
+            .into_iter()
+            .filter_map(Result::ok)
+        {
+            if entry.file_type().is_file() && entry.file_name() == MCP_FILE_NAME {
+                let path = entry.into_path();
+                let tool_name = path
+                    .parent()
+                    .and_then(|p| p.file_name())
+                    .and_then(|os| os.to_str())
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+                // 홈 디렉터리에서 찾은 도구와 이름이 겹치면 서로 덮어쓰지 않도록 `root:` 를 붙입니다.
+                let name = if found.iter().any(|tool| tool.name == tool_name) {
+                    format!("root:{tool_name}")
+                } else {
+                    tool_name.clone()
+                };
+                found.push(ToolRecord {
+                    agent: tool_name,
+                    ..ToolRecord::new(name, path)
+                });
+            }
+        }
+    }
+
+    let secrets = db.load_secrets()?;
+    let mut tools = Vec::new();
+    for record in found {
+        db.record_tool_detection(&record)?;
+        match load_tool(&record, &secrets) {
+            Ok(config) => tools.push(config),
+            Err(err) => {
+                tracing::warn!(tool = %record.name, path = %record.config_path.display(), "failed to load tool config: {err}")
+            }
         }
     }
     Ok(tools)
 }
 
-pub fn read_settings_from_file(path: &Path) -> BackendResult<McpSettings> {
+/// 설정 파일을 읽어 검색된 형식이나 에이전트 이름과 내용으로 어댑터를 고른 뒤 `ToolConfiguration` 을 만듭니다.
+/// 설치는 되었지만 아직 설정 파일이 없는 도구는 빈 구성으로 취급합니다.
+/// 파일에 기록된 비밀 값은 `${secret:이름}` 참조로 되돌려 마스터와 비교할 수 있게 합니다.
+pub fn load_tool(record: &ToolRecord, secrets: &Secrets) -> BackendResult<ToolConfiguration> {
+    let path = record.config_path.as_path();
+    let content = read_existing(path)?;
+    let kind = record.agent_kind(content.as_deref().unwrap_or_default());
+    let settings = match content {
+        Some(content) => secrets.conceal(&kind.adapter_for(path).parse(&content)?),
+        None => McpSettings::default(),
+    };
+    let mut tool = ToolConfiguration::new(&record.name, kind, path.to_string_lossy(), settings);
+    tool.project = record.project.clone();
+    tool.policy = record.policy;
+    tool.env_mode = record.env_mode;
+    tool.profile = record.profile.clone();
+    Ok(tool)
+}
+
+pub fn read_settings_from_file(path: &Path, kind: AgentKind) -> BackendResult<McpSettings> {
     let content = fs::read_to_string(path)?;
-    let settings: McpSettings = serde_json::from_str(&content)?;
-    Ok(settings)
+    kind.adapter_for(path).parse(&content)
+}
+
+pub fn write_settings_to_file(
+    path: &Path,
+    kind: AgentKind,
+    settings: &McpSettings,
+) -> BackendResult<()> {
+    let existing = read_existing(path)?;
+    let (settings, warnings) = conform_settings(settings, kind);
+    for warning in warnings {
+        tracing::warn!(path = %path.display(), "{warning}");
+    }
+    let rendered = kind
+        .adapter_for(path)
+        .render(&settings, existing.as_deref())?;
+    write_file(path, &rendered)
 }
 
-pub fn write_settings_to_file(path: &Path, settings: &McpSettings) -> BackendResult<()> {
-    let json = serde_json::to_string_pretty(settings)?;
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+fn read_existing(path: &Path) -> BackendResult<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
     }
-    fs::write(path, json)?;
+}
+
+/// 같은 디렉터리의 임시 파일에 기록하고 fsync 한 뒤 rename 하므로, 중간에 중단되어도
+/// 잘린 설정 파일이 남지 않습니다. 기존 파일의 권한과 소유자는 그대로 유지합니다.
+fn write_file(path: &Path, content: &str) -> BackendResult<()> {
+    // 심볼릭 링크(dotfiles 저장소 등)는 링크 자체가 아니라 대상 파일을 교체합니다.
+    let path = match fs::canonicalize(path) {
+        Ok(resolved) => resolved,
+        Err(_) => path.to_path_buf(),
+    };
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    fs::create_dir_all(&parent)?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| BackendError::Other(format!("invalid config path: {}", path.display())))?;
+    let original = fs::metadata(&path).ok();
+
+    let temp_path = parent.join(format!(
+        ".{}.{}-{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = write_temp_and_rename(&temp_path, &path, content, original.as_ref());
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result?;
+
+    #[cfg(unix)]
+    fs::File::open(&parent)?.sync_all()?;
     Ok(())
 }
 
+fn write_temp_and_rename(
+    temp_path: &Path,
+    path: &Path,
+    content: &str,
+    original: Option<&fs::Metadata>,
+) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if original.is_some() {
+        // 권한을 옮겨 오기 전까지 다른 사용자가 내용을 읽을 수 없도록 합니다.
+        options.mode(0o600);
+    }
+    let mut file = options.open(temp_path)?;
+
+    if let Some(original) = original {
+        fs::set_permissions(temp_path, original.permissions())?;
+        #[cfg(unix)]
+        {
+            let (uid, gid) = (original.uid(), original.gid());
+            let current = fs::metadata(temp_path)?;
+            if current.uid() != uid || current.gid() != gid {
+                // 권한이 없어 소유자를 바꿀 수 없는 경우는 무시하고 권한 비트만 유지합니다.
+                let _ = std::os::unix::fs::chown(temp_path, Some(uid), Some(gid));
+            }
+        }
+    }
+
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(temp_path, path)
+}
+
+/// `sync_tool` 이 기록할 내용을 파일이나 동기화 이력에 손대지 않고 계산합니다.
+/// `base` 는 `Database::find_sync_base` 로 읽은 마지막 동기화 기준이며, 마스터의 비밀 참조는
+/// `secrets` 로 풀어 `proposed` 에만 기록합니다.
+pub fn plan_tool(
+    tool: &ToolConfiguration,
+    master: &McpSettings,
+    base: Option<&SyncBase>,
+    secrets: &Secrets,
+) -> BackendResult<SyncPlan> {
+    prepare_plan(tool, master, base, secrets).map(|(plan, _)| plan)
+}
+
+/// 계획과 함께, 동기화 후 저장할 새 기준(마스터를 도구 형식으로 옮긴 구성)을 반환합니다.
+fn prepare_plan(
+    tool: &ToolConfiguration,
+    master: &McpSettings,
+    base: Option<&SyncBase>,
+    secrets: &Secrets,
+) -> BackendResult<(SyncPlan, SyncBase)> {
+    let existing = read_existing(Path::new(&tool.config_path))?;
+    let master = target_master(tool, master);
+    let loaded = &tool.settings;
+    let tool = &with_env_references(tool, &master);
+    let merged = merge_settings(&master, &tool.settings, tool.policy);
+    let (normalized, warnings) = conform_settings(&merged, tool.kind);
+    let adapter = tool.kind.adapter_for(Path::new(&tool.config_path));
+    let mut resolved = resolve_values(tool, &normalized, secrets)?;
+    let mut proposed = adapter.render(&resolved, existing.as_deref())?;
+    let mut written = secrets.conceal(&adapter.parse(&proposed)?);
+    let mut proposed_settings = unexpand_env(tool.env_mode, &written, &master);
+    let mut next_base = proposed_settings.clone();
+    // 로컬 수정과 합치기 전, 마스터 값을 풀어 기록한 형태입니다.
+    let expanded = (tool.env_mode == EnvMode::Expand).then(|| written.clone());
+    let mut conflicts = Vec::new();
+    // 미러 정책은 로컬 수정과 합치지 않고 마스터를 그대로 기록합니다.
+    if let Some(base) = base.filter(|_| tool.policy != SyncPolicy::Mirror) {
+        // 환경 변수 값이 바뀌어도 지난번에 풀어 기록한 그대로인 값은 로컬 수정으로 보지 않습니다.
+        let local = match &base.expanded {
+            Some(expanded) if tool.env_mode == EnvMode::Expand => {
+                interpolate::unexpand_previous(&tool.settings, &base.settings, expanded)
+            }
+            _ => tool.settings.clone(),
+        };
+        let reconciled = reconcile_with_base(&base.settings, &local, &proposed_settings)?;
+        if reconciled.settings != proposed_settings {
+            resolved = resolve_values(tool, &reconciled.settings, secrets)?;
+            proposed = adapter.render(&resolved, existing.as_deref())?;
+            written = secrets.conceal(&adapter.parse(&proposed)?);
+            proposed_settings = unexpand_env(tool.env_mode, &written, &master);
+        }
+        next_base = reconciled.base;
+        conflicts = reconciled.conflicts;
+    }
+    let next_base = SyncBase {
+        settings: next_base,
+        expanded,
+    };
+    // 풀린 값과 참조가 섞인 파일도 다시 기록하도록 실제로 기록될 형태로 비교합니다.
+    let changed = existing.as_deref() != Some(proposed.as_str())
+        && (existing.is_none() || written != *loaded);
+    let changes = if changed {
+        diff::diff_settings(&proposed_settings, &tool.settings)
+    } else {
+        SettingsDiff::default()
+    };
+    // 기록할 글자에서 찾아 바꾸면 이스케이프된 값을 놓치므로 가린 구성을 따로 렌더링합니다.
+    let proposed_redacted = adapter.render(&resolved.redacted(), existing.as_deref())?;
+    let plan = SyncPlan {
+        tool: tool.name.clone(),
+        config_path: tool.config_path.clone(),
+        changed,
+        proposed,
+        proposed_redacted,
+        changes,
+        warnings,
+        conflicts,
+        error: None,
+    };
+    Ok((plan, next_base))
+}
+
+/// 파일에 기록할 값. 비밀 참조는 항상 풀고, 환경 변수 참조는 도구의 `env_mode` 에 따릅니다.
+fn resolve_values(
+    tool: &ToolConfiguration,
+    settings: &McpSettings,
+    secrets: &Secrets,
+) -> BackendResult<McpSettings> {
+    match tool.env_mode {
+        EnvMode::Expand => secrets.resolve(&interpolate::expand(settings)?),
+        EnvMode::Passthrough => secrets.resolve(settings),
+    }
+}
+
+/// 도구 파일에 풀려 기록된 환경 변수 값을 마스터의 `${env:...}` 참조로 되돌린 구성.
+/// 병합과 비교는 이 형태로 하므로 환경 변수 값이 달라 보이는 차이가 생기지 않습니다.
+pub fn with_env_references(tool: &ToolConfiguration, master: &McpSettings) -> ToolConfiguration {
+    let mut tool = tool.clone();
+    tool.settings = unexpand_env(tool.env_mode, &tool.settings, master);
+    tool
+}
+
+fn unexpand_env(mode: EnvMode, settings: &McpSettings, master: &McpSettings) -> McpSettings {
+    match mode {
+        EnvMode::Expand => interpolate::unexpand(settings, master),
+        EnvMode::Passthrough => settings.clone(),
+    }
+}
+
 pub fn sync_tool(
     tool: &ToolConfiguration,
     master: &McpSettings,
     db: &Database,
 ) -> BackendResult<SyncSummary> {
-    let current = &tool.settings;
-    if current == master {
-        let summary = SyncSummary {
-            tool: tool.name.clone(),
-            status: SyncStatus::Skipped,
-            message: "Already up-to-date".to_string(),
-            synced_at: Utc::now(),
-        };
-        db.record_sync(&summary)?;
-        return Ok(summary);
-    }
+    let base = db.find_sync_base(&tool.name)?;
+    let secrets = db.load_secrets()?;
+    let (plan, next_base) = prepare_plan(tool, master, base.as_ref(), &secrets)?;
+    let status = if !plan.conflicts.is_empty() {
+        SyncStatus::Conflict
+    } else if plan.changed {
+        SyncStatus::Updated
+    } else {
+        SyncStatus::Skipped
+    };
+    let message = match status {
+        SyncStatus::Conflict => conflict_message(tool, &plan.conflicts),
+        SyncStatus::Skipped => "Already up-to-date".to_string(),
+        _ => "Configuration updated".to_string(),
+    };
 
-    let normalized = merge_settings(master, current);
-    write_settings_to_file(Path::new(&tool.config_path), &normalized)?;
+    let path = Path::new(&tool.config_path);
+    let backup_id = if plan.changed {
+        Some(write_with_backup(
+            db,
+            &tool.name,
+            path,
+            Some(&plan.proposed),
+        )?)
+    } else {
+        None
+    };
 
-    let summary = SyncSummary {
+    let mut summary = SyncSummary {
+        id: None,
         tool: tool.name.clone(),
+        status,
+        message: with_warnings(&message, &plan.warnings),
+        synced_at: Utc::now(),
+    };
+    summary.id = Some(db.record_sync_run(&summary, Some(&next_base), backup_id)?);
+    Ok(summary)
+}
+
+/// 현재 파일 내용을 백업으로 저장한 뒤 덮어씁니다. 쓰기에 실패하면 백업을 지웁니다.
+/// `content` 가 `None` 이면 파일을 삭제합니다.
+fn write_with_backup(
+    db: &Database,
+    tool_name: &str,
+    path: &Path,
+    content: Option<&str>,
+) -> BackendResult<i64> {
+    let previous = read_existing(path)?;
+    let backup_id = db.record_backup(tool_name, path, previous.as_deref())?;
+    let written = match content {
+        Some(content) => write_file(path, content),
+        None if previous.is_some() => fs::remove_file(path).map_err(Into::into),
+        None => Ok(()),
+    };
+    if let Err(err) = written {
+        db.discard_backup(backup_id)?;
+        return Err(err);
+    }
+    Ok(backup_id)
+}
+
+fn conflict_message(tool: &ToolConfiguration, conflicts: &[SyncConflict]) -> String {
+    let fields: Vec<String> = conflicts.iter().map(ToString::to_string).collect();
+    format!(
+        "Kept tool values for {} conflicting change(s): {} (resolve with `agentctl resolve --agent {} --server <id> --take master|tool`)",
+        conflicts.len(),
+        fields.join(", "),
+        tool.name
+    )
+}
+
+fn with_warnings(message: &str, warnings: &[String]) -> String {
+    if warnings.is_empty() {
+        message.to_string()
+    } else {
+        format!("{message} (warnings: {})", warnings.join("; "))
+    }
+}
+
+/// 등록된 도구 하나를 읽어 도구에 지정된 프로필로 동기화합니다. 파일을 읽거나 쓰는 중 실패하면
+/// `Failed` 요약을 이력에 남기고 반환하므로, 호출 측은 나머지 도구를 계속 동기화할 수 있습니다.
+/// 실패 기록 자체가 불가능한 경우(데이터베이스 오류)에만 `Err` 를 반환합니다.
+pub fn sync_registered_tool(db: &Database, record: &ToolRecord) -> BackendResult<SyncSummary> {
+    let name = record.name.as_str();
+    let result = db
+        .profile_settings(record.profile.as_deref())
+        .and_then(|master| {
+            let tool = load_tool(record, &db.load_secrets()?)?;
+            sync_tool(&tool, &master, db)
+        });
+    match result {
+        Ok(summary) => Ok(summary),
+        Err(BackendError::Database(err)) => Err(BackendError::Database(err)),
+        Err(err) => {
+            let message = failure_message(db, &err);
+            tracing::warn!(tool = name, "sync failed: {message}");
+            let mut summary = SyncSummary {
+                id: None,
+                tool: name.to_string(),
+                status: SyncStatus::Failed,
+                message,
+                synced_at: Utc::now(),
+            };
+            summary.id = Some(db.record_sync(&summary)?);
+            Ok(summary)
+        }
+    }
+}
+
+/// `sync_registered_tool` 의 드라이런. 도구 설정을 읽거나 계획을 세우지 못하면 `error` 를 채운
+/// 계획을 반환하므로, 호출 측은 나머지 도구의 계획을 계속 만들 수 있습니다.
+pub fn plan_registered_tool(db: &Database, record: &ToolRecord) -> BackendResult<SyncPlan> {
+    let result = db
+        .profile_settings(record.profile.as_deref())
+        .and_then(|master| {
+            let secrets = db.load_secrets()?;
+            let tool = load_tool(record, &secrets)?;
+            let base = db.find_sync_base(&record.name)?;
+            plan_tool(&tool, &master, base.as_ref(), &secrets)
+        });
+    match result {
+        Ok(plan) => Ok(plan),
+        Err(BackendError::Database(err)) => Err(BackendError::Database(err)),
+        Err(err) => Ok(SyncPlan::failed(
+            record.name.as_str(),
+            record.config_path.display().to_string(),
+            failure_message(db, &err),
+        )),
+    }
+}
+
+/// 파싱 오류 등에 설정 파일 내용이 섞여 나올 수 있으므로, 이력과 응답에 남기기 전에 자격 증명을 가립니다.
+fn failure_message(db: &Database, err: &BackendError) -> String {
+    let message = match db.load_secrets() {
+        Ok(secrets) => secrets.conceal_text(&err.to_string()),
+        Err(_) => err.to_string(),
+    };
+    redact::redact_text(&message)
+}
+
+/// 동기화 직전에 저장한 스냅샷으로 도구 설정 파일을 되돌립니다.
+/// 복원 자체도 하나의 동기화로 기록되므로, 복원 직전 내용 역시 다시 되돌릴 수 있습니다.
+pub fn restore_tool(
+    db: &Database,
+    tool_name: &str,
+    sync_id: Option<i64>,
+) -> BackendResult<SyncSummary> {
+    let backup = db
+        .find_backup(tool_name, sync_id)?
+        .ok_or_else(|| match sync_id {
+            Some(id) => BackendError::NotFound(format!("backup of '{tool_name}' for sync #{id}")),
+            None => BackendError::NotFound(format!("backup of '{tool_name}'")),
+        })?;
+
+    let path = backup.config_path.as_path();
+    let backup_id = write_with_backup(db, tool_name, path, backup.content.as_deref())?;
+
+    let message = match backup.sync_id {
+        Some(id) => format!("Restored snapshot taken before sync #{id}"),
+        None => "Restored snapshot taken before an unrecorded sync".to_string(),
+    };
+    let mut summary = SyncSummary {
+        id: None,
+        tool: tool_name.to_string(),
         status: SyncStatus::Updated,
-        message: "Configuration updated".to_string(),
+        message,
         synced_at: Utc::now(),
     };
-    db.record_sync(&summary)?;
+    summary.id = Some(db.record_sync_run(&summary, None, Some(backup_id))?);
     Ok(summary)
 }
 
-pub fn merge_settings(master: &McpSettings, tool: &McpSettings) -> McpSettings {
+/// 충돌한 서버의 기준을 고른 쪽에 맞춘 뒤 다시 동기화합니다. `Master` 를 고르면 마스터 값이
+/// 기록되고, `Tool` 을 고르면 마스터가 다시 바뀔 때까지 도구 값이 유지됩니다.
+pub fn resolve_conflict(
+    db: &Database,
+    record: &ToolRecord,
+    server_id: &str,
+    take: ConflictSide,
+) -> BackendResult<SyncSummary> {
+    let master = &db.profile_settings(record.profile.as_deref())?;
+    let secrets = db.load_secrets()?;
+    let tool = load_tool(record, &secrets)?;
+    let base = db.find_sync_base(&tool.name)?;
+    let plan = plan_tool(&tool, master, base.as_ref(), &secrets)?;
+    let conflicts: Vec<_> = plan
+        .conflicts
+        .into_iter()
+        .filter(|conflict| conflict.server == server_id)
+        .collect();
+    let SyncBase {
+        settings: mut base,
+        expanded,
+    } = match base {
+        Some(base) if !conflicts.is_empty() => base,
+        _ => {
+            return Err(BackendError::NotFound(format!(
+                "conflict on server '{server_id}' of '{}'",
+                tool.name
+            )))
+        }
+    };
+
+    for conflict in conflicts {
+        // 고른 쪽이 바뀌지 않은 것처럼 기준을 맞추면 다음 병합에서 그쪽 값이 남습니다.
+        let agreed = match take {
+            ConflictSide::Master => conflict.tool,
+            ConflictSide::Tool => conflict.master,
+        };
+        let position = base
+            .servers
+            .iter()
+            .position(|server| server.id == server_id);
+        match (conflict.field, position) {
+            (None, Some(index)) => match agreed {
+                Value::Null => {
+                    base.servers.remove(index);
+                }
+                server => base.servers[index] = serde_json::from_value(server)?,
+            },
+            (Some(field), Some(index)) => {
+                let mut server = match serde_json::to_value(&base.servers[index])? {
+                    Value::Object(server) => server,
+                    _ => Map::new(),
+                };
+                match agreed {
+                    Value::Null => server.remove(&field),
+                    value => server.insert(field, value),
+                };
+                base.servers[index] = serde_json::from_value(Value::Object(server))?;
+            }
+            (_, None) => {}
+        }
+    }
+    db.record_sync_base(
+        &tool.name,
+        &SyncBase {
+            settings: base,
+            expanded,
+        },
+    )?;
+    sync_tool(&tool, master, db)
+}
+
+/// 도구에 기록할 마스터 구성. 프로젝트 단위 설정 파일은 해당 프로젝트의 오버라이드가
+/// 가리키는 서버만 받습니다.
+pub fn target_master(tool: &ToolConfiguration, master: &McpSettings) -> McpSettings {
+    match &tool.project {
+        Some(project) => master.for_project(project),
+        None => master.clone(),
+    }
+}
+
+/// 에이전트가 표현할 수 없는 항목을 걸러 냅니다. 비활성 서버는 `enabled` 를 기록할 수 없는
+/// 에이전트에서 생략하고, 지원하지 않는 원격 전송 방식은 stdio 가 가능하면 `mcp-remote` 브리지로
+/// 바꿉니다. 그 밖에 지원하지 않는 서버나 필드는 경고와 함께 생략합니다.
+pub fn conform_settings(settings: &McpSettings, kind: AgentKind) -> (McpSettings, Vec<String>) {
+    let capabilities = kind.adapter().capabilities();
+    let mut conformed = settings.clone();
+    let mut warnings = Vec::new();
+    conformed.servers.retain_mut(|server| {
+        if !server.enabled && !capabilities.enabled_flag {
+            return false;
+        }
+        let supported = match server.transport {
+            McpTransport::Stdio => capabilities.stdio,
+            McpTransport::StreamableHttp => capabilities.streamable_http,
+            McpTransport::Sse => capabilities.sse,
+        };
+        if !supported {
+            let transport = server.transport.as_str();
+            if server.is_remote() && capabilities.stdio {
+                bridge_through_mcp_remote(server);
+                warnings.push(format!(
+                    "{}: {kind} does not support {transport} servers, bridged through mcp-remote",
+                    server.id
+                ));
+            } else {
+                warnings.push(format!(
+                    "{}: {kind} does not support {transport} servers, skipped",
+                    server.id
+                ));
+                return false;
+            }
+        }
+        // stdio 서버에는 `api_key` 를 실을 자리가 없으므로 조용히 사라지지 않게 알립니다.
+        if server.transport == McpTransport::Stdio
+            && server.api_key.take().is_some_and(|key| !key.is_empty())
+        {
+            warnings.push(format!(
+                "{}: api_key is not supported for stdio servers, dropped; use env instead",
+                server.id
+            ));
+        }
+        if !capabilities.env && !server.env.is_empty() {
+            server.env.clear();
+            warnings.push(format!(
+                "{}: {kind} does not support env, dropped",
+                server.id
+            ));
+        }
+        if !capabilities.cwd && server.cwd.is_some() {
+            server.cwd = None;
+            warnings.push(format!(
+                "{}: {kind} does not support cwd, dropped",
+                server.id
+            ));
+        }
+        true
+    });
+    (conformed, warnings)
+}
+
+/// 원격 서버의 `headers` 와 `api_key` 는 `mcp-remote` 의 `--header 이름:값` 인자로 넘깁니다.
+fn bridge_through_mcp_remote(server: &mut McpServer) {
+    let mut args = vec![
+        "-y".to_string(),
+        "mcp-remote".to_string(),
+        std::mem::take(&mut server.endpoint),
+    ];
+    if server.transport == McpTransport::Sse {
+        args.extend(["--transport".to_string(), "sse-only".to_string()]);
+    }
+    let mut header = |name: &str, value: &str| {
+        args.extend(["--header".to_string(), format!("{name}:{value}")]);
+    };
+    if let Some(Value::Object(headers)) = server.extra.shift_remove("headers") {
+        for (name, value) in &headers {
+            if let Some(value) = value.as_str() {
+                header(name, value);
+            }
+        }
+    }
+    if let Some(api_key) = server.api_key.take().filter(|key| !key.is_empty()) {
+        header("Authorization", &format!("Bearer {api_key}"));
+    }
+    server.transport = McpTransport::Stdio;
+    server.command = Some("npx".to_string());
+    server.args = args;
+}
+
+/// 정책에 따라 마스터와 도구 구성을 합칩니다. 어느 정책이든 상대편에 없는 에이전트 고유 키는 유지합니다.
+pub fn merge_settings(master: &McpSettings, tool: &McpSettings, policy: SyncPolicy) -> McpSettings {
     if tool == master {
         return tool.clone();
     }
+    if policy == SyncPolicy::PreserveLocal {
+        // 도구 구성을 기준으로 삼고, 도구에 없는 마스터 항목만 덧붙입니다.
+        let mut merged = tool.clone();
+        let known_servers: HashSet<_> = tool.servers.iter().map(|server| &server.id).collect();
+        for server in &master.servers {
+            if !known_servers.contains(&server.id) {
+                merged.servers.push(server.clone());
+            }
+        }
+        merged.project_overrides =
+            merge_project_overrides(&tool.project_overrides, &master.project_overrides, true);
+        carry_over_extra(&mut merged.extra, &master.extra);
+        return merged;
+    }
 
+    let keep_tool_only = policy == SyncPolicy::Additive;
     let mut merged = master.clone();
     let mut known_servers: HashSet<_> = merged
         .servers
@@ -100,27 +700,163 @@ pub fn merge_settings(master: &McpSettings, tool: &McpSettings) -> McpSettings {
 
     for server in &tool.servers {
         if known_servers.insert(server.id.clone()) {
-            merged.servers.push(server.clone());
+            if keep_tool_only {
+                merged.servers.push(server.clone());
+            }
+        } else if let Some(target) = merged.servers.iter_mut().find(|item| item.id == server.id) {
+            carry_over_extra(&mut target.extra, &server.extra);
         }
     }
 
-    merged.project_overrides =
-        merge_project_overrides(&master.project_overrides, &tool.project_overrides);
+    merged.project_overrides = merge_project_overrides(
+        &master.project_overrides,
+        &tool.project_overrides,
+        keep_tool_only,
+    );
+    carry_over_extra(&mut merged.extra, &tool.extra);
     merged
 }
 
+/// 마스터에 없는 에이전트 고유 키는 도구 쪽 값을 유지합니다.
+fn carry_over_extra(target: &mut Map<String, Value>, tool_extra: &Map<String, Value>) {
+    for (key, value) in tool_extra {
+        if !target.contains_key(key) {
+            target.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+/// `primary` 를 기준으로 `others` 의 같은 프로젝트 항목에서 고유 키를 옮겨 오고,
+/// `append` 이면 `primary` 에 없는 프로젝트 항목도 덧붙입니다.
 fn merge_project_overrides(
-    master_overrides: &[ProjectOverride],
-    overrides: &[ProjectOverride],
+    primary: &[ProjectOverride],
+    others: &[ProjectOverride],
+    append: bool,
 ) -> Vec<ProjectOverride> {
-    let mut merged = master_overrides.to_vec();
+    let mut merged = primary.to_vec();
     let mut seen: HashSet<_> = merged.iter().map(|item| item.project.clone()).collect();
 
-    for override_entry in overrides {
+    for override_entry in others {
         if seen.insert(override_entry.project.clone()) {
-            merged.push(override_entry.clone());
+            if append {
+                merged.push(override_entry.clone());
+            }
+        } else if let Some(target) = merged
+            .iter_mut()
+            .find(|item| item.project == override_entry.project)
+        {
+            carry_over_extra(&mut target.extra, &override_entry.extra);
         }
     }
 
     merged
 }
+
+struct Reconciled {
+    settings: McpSettings,
+    base: McpSettings,
+    conflicts: Vec<SyncConflict>,
+}
+
+/// 마지막 동기화 기준(`base`)과 비교해 3-way 병합합니다. `proposed` 는 마스터를 반영한 구성이며,
+/// 도구에서만 바뀐 필드는 도구 값을 유지하고 양쪽에서 다르게 바뀐 필드는 충돌로 남깁니다.
+/// 충돌한 필드는 새 기준에서도 이전 기준 값을 유지하므로 해결될 때까지 계속 보고됩니다.
+fn reconcile_with_base(
+    base: &McpSettings,
+    tool: &McpSettings,
+    proposed: &McpSettings,
+) -> BackendResult<Reconciled> {
+    let mut settings = proposed.clone();
+    let mut next_base = proposed.clone();
+    let mut conflicts = Vec::new();
+    let find = |settings: &McpSettings, id: &str| -> Option<McpServer> {
+        settings
+            .servers
+            .iter()
+            .find(|server| server.id == id)
+            .cloned()
+    };
+
+    settings.servers.clear();
+    for (index, server) in proposed.servers.iter().enumerate() {
+        let Some(base_server) = find(base, &server.id) else {
+            settings.servers.push(server.clone());
+            continue;
+        };
+        let Some(tool_server) = find(tool, &server.id) else {
+            // 도구에서 삭제한 서버는 마스터가 그대로일 때만 삭제 상태를 유지합니다.
+            if *server != base_server {
+                conflicts.push(SyncConflict {
+                    server: server.id.clone(),
+                    field: None,
+                    master: serde_json::to_value(server)?,
+                    tool: Value::Null,
+                });
+                next_base.servers[index] = base_server;
+            }
+            continue;
+        };
+
+        let proposed_fields = server_fields(server)?;
+        let base_fields = server_fields(&base_server)?;
+        let tool_fields = server_fields(&tool_server)?;
+        let mut merged = proposed_fields.clone();
+        let mut agreed = proposed_fields.clone();
+        let mut seen = HashSet::new();
+        for key in proposed_fields
+            .keys()
+            .chain(tool_fields.keys())
+            .chain(base_fields.keys())
+        {
+            if !seen.insert(key) {
+                continue;
+            }
+            let (ours, ancestor, theirs) = (
+                proposed_fields.get(key),
+                base_fields.get(key),
+                tool_fields.get(key),
+            );
+            if theirs == ancestor || ours == theirs {
+                continue;
+            }
+            if ours != ancestor {
+                conflicts.push(SyncConflict {
+                    server: server.id.clone(),
+                    field: Some(key.clone()),
+                    master: ours.cloned().unwrap_or(Value::Null),
+                    tool: theirs.cloned().unwrap_or(Value::Null),
+                });
+                set_field(&mut agreed, key, ancestor);
+            }
+            set_field(&mut merged, key, theirs);
+        }
+        settings
+            .servers
+            .push(serde_json::from_value(Value::Object(merged))?);
+        next_base.servers[index] = serde_json::from_value(Value::Object(agreed))?;
+    }
+
+    Ok(Reconciled {
+        settings,
+        base: next_base,
+        conflicts,
+    })
+}
+
+fn server_fields(server: &McpServer) -> BackendResult<Map<String, Value>> {
+    match serde_json::to_value(server)? {
+        Value::Object(fields) => Ok(fields),
+        _ => Ok(Map::new()),
+    }
+}
+
+fn set_field(fields: &mut Map<String, Value>, key: &str, value: Option<&Value>) {
+    match value {
+        Some(value) => {
+            fields.insert(key.to_string(), value.clone());
+        }
+        None => {
+            fields.shift_remove(key);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use backend::adapter::{select_adapter, AgentKind};
+
+const MCP_SERVERS: &str = r#"{"mcpServers": {"fs": {"command": "npx"}}}"#;
+const CODEX_TOML: &str = "[mcp_servers.fs]\ncommand = \"npx\"\n";
+const NATIVE: &str = r#"{"servers": []}"#;
+
+#[test]
+fn name_wins_when_content_matches_or_is_empty() {
+    assert_eq!(select_adapter("cursor", MCP_SERVERS), AgentKind::Cursor);
+    assert_eq!(select_adapter("Windsurf", MCP_SERVERS), AgentKind::Windsurf);
+    assert_eq!(select_adapter("zed", ""), AgentKind::Zed);
+    assert_eq!(select_adapter("codex", "  \n"), AgentKind::Codex);
+}
+
+#[test]
+fn content_signature_overrides_a_mismatched_name() {
+    // 이름은 cursor 지만 내용이 Codex TOML 이면 내용을 따릅니다.
+    assert_eq!(select_adapter("cursor", CODEX_TOML), AgentKind::Codex);
+    // 이름을 모르면 `AgentKind::ALL` 순서에서 처음 맞는 형식을 고릅니다.
+    assert_eq!(select_adapter("unknown", NATIVE), AgentKind::Native);
+    assert_eq!(
+        select_adapter("unknown", MCP_SERVERS),
+        AgentKind::ClaudeDesktop
+    );
+    // 어느 형식과도 맞지 않으면 이름, 이름도 모르면 기본 형식입니다.
+    assert_eq!(select_adapter("zed", "not a config"), AgentKind::Zed);
+    assert_eq!(select_adapter("unknown", "not a config"), AgentKind::Native);
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use std::fs;
+use std::os::unix::fs::{symlink, PermissionsExt};
+
+use backend::adapter::AgentKind;
+use backend::config::{McpServer, McpSettings};
+use backend::sync::write_settings_to_file;
+use common::Scratch;
+
+fn master() -> McpSettings {
+    McpSettings {
+        servers: vec![McpServer::default()],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn replaces_file_atomically_and_keeps_mode() {
+    let dir = Scratch::new("atomic");
+    fs::create_dir_all(dir.join("dotfiles")).unwrap();
+    let path = dir.join("dotfiles/mcp.json");
+    fs::write(&path, "{\"theme\": \"dark\", \"mcpServers\": {}}").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+    let link = dir.join("mcp.json");
+    symlink(&path, &link).unwrap();
+
+    write_settings_to_file(&link, AgentKind::ClaudeDesktop, &master()).unwrap();
+
+    // 링크는 그대로 두고 대상 파일을 새 내용으로 교체합니다.
+    assert!(fs::symlink_metadata(&link)
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    let written = fs::read_to_string(&path).unwrap();
+    assert!(written.contains("\"theme\": \"dark\""));
+    assert!(written.contains("http://localhost:3001"));
+    let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode, 0o600);
+    // 임시 파일이 남지 않습니다.
+    let names: Vec<_> = fs::read_dir(dir.join("dotfiles"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(names, ["mcp.json"]);
+
+    let created = dir.join("new/mcp.json");
+    write_settings_to_file(&created, AgentKind::ClaudeDesktop, &master()).unwrap();
+    assert!(fs::read_to_string(&created)
+        .unwrap()
+        .contains("http://localhost:3001"));
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use backend::adapter::AgentKind;
+use backend::audit::{migrate_secrets, scan_tool};
+use backend::config::{McpServer, McpSettings, RevisionMeta, RevisionSource, ToolConfiguration};
+use common::Scratch;
+
+fn github(token: &str) -> McpServer {
+    let mut server = McpServer {
+        id: "github".to_string(),
+        command: Some("npx".to_string()),
+        args: vec![
+            "gh-mcp".to_string(),
+            "--token".to_string(),
+            token.to_string(),
+        ],
+        ..McpServer::default()
+    };
+    server
+        .env
+        .insert("GITHUB_TOKEN".to_string(), token.to_string());
+    server
+        .env
+        .insert("LOG_LEVEL".to_string(), "debug".to_string());
+    server
+}
+
+fn settings_with(server: McpServer) -> McpSettings {
+    McpSettings {
+        servers: vec![server],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn plaintext_tokens_are_reported_and_migrated() {
+    let token = "ghp_abcdefghijklmnop1234";
+    let anthropic = McpServer {
+        id: "anthropic".to_string(),
+        endpoint: "https://api.example/mcp".to_string(),
+        api_key: Some("${secret:anthropic_key}".to_string()),
+        ..McpServer::default()
+    };
+    let mut settings = settings_with(github(token));
+    settings.servers.push(anthropic);
+    let tool = ToolConfiguration::new("cursor", AgentKind::Cursor, "/tmp/mcp.json", settings);
+
+    let findings = scan_tool(&tool);
+    let fields: Vec<_> = findings
+        .iter()
+        .map(|finding| (finding.server.as_str(), finding.field.as_str()))
+        .collect();
+    assert_eq!(
+        fields,
+        [("github", "args[2]"), ("github", "env.GITHUB_TOKEN")]
+    );
+    assert!(findings.iter().all(|finding| finding.preview == "ghp_****"));
+    assert!(!serde_json::to_string(&findings).unwrap().contains(token));
+
+    let dir = Scratch::new("audit");
+    let db = dir.database();
+    db.upsert_master_config(
+        &settings_with(github(token)),
+        &RevisionMeta::new(RevisionSource::Cli, None),
+    )
+    .unwrap();
+    db.create_profile("work", &settings_with(github(token)))
+        .unwrap();
+    db.create_profile("clean", &McpSettings::default()).unwrap();
+
+    let migration = migrate_secrets(
+        &db,
+        &findings,
+        &RevisionMeta::new(RevisionSource::Cli, None),
+    )
+    .unwrap();
+    assert_eq!(migration.stored, ["github.github_token"]);
+    assert!(migration.revision.is_some());
+    assert_eq!(migration.profiles, ["work"]);
+    assert_eq!(
+        db.profile_settings(Some("work")).unwrap(),
+        settings_with(github("${secret:github.github_token}"))
+    );
+    assert_eq!(
+        db.get_secret("github.github_token").unwrap().as_deref(),
+        Some(token)
+    );
+    assert_eq!(
+        db.ensure_master_config().unwrap().settings,
+        settings_with(github("${secret:github.github_token}"))
+    );
+
+    // 이름 있는 프로필의 이전도 리비전으로 남아 되돌릴 수 있습니다.
+    let revisions = db.list_profile_revisions("work", 10).unwrap();
+    assert_eq!(revisions.len(), 2);
+    db.rollback_profile(
+        "work",
+        revisions[1].id,
+        RevisionMeta::new(RevisionSource::Cli, None),
+    )
+    .unwrap();
+    assert_eq!(
+        db.profile_settings(Some("work")).unwrap(),
+        settings_with(github(token))
+    );
+}
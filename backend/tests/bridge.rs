@@ -0,0 +1,72 @@
+use backend::adapter::AgentKind;
+use backend::config::{McpServer, McpSettings, McpTransport};
+use backend::redact::Redact;
+use backend::sync::conform_settings;
+use serde_json::json;
+
+#[test]
+fn bridge_forwards_headers_and_api_key() {
+    let mut server = McpServer {
+        id: "docs".to_string(),
+        transport: McpTransport::Sse,
+        endpoint: "https://docs.dev/sse".to_string(),
+        api_key: Some("sk-abcdefghijklmnop".to_string()),
+        ..McpServer::default()
+    };
+    server
+        .extra
+        .insert("headers".to_string(), json!({ "X-Trace": "1" }));
+    let settings = McpSettings {
+        servers: vec![server],
+        ..Default::default()
+    };
+
+    // Claude Desktop 은 원격 서버를 지원하지 않아 mcp-remote 로 연결합니다.
+    let (conformed, warnings) = conform_settings(&settings, AgentKind::ClaudeDesktop);
+    assert_eq!(warnings.len(), 1);
+    let bridged = &conformed.servers[0];
+    assert_eq!(bridged.transport, McpTransport::Stdio);
+    assert_eq!(bridged.command.as_deref(), Some("npx"));
+    assert_eq!(
+        bridged.args,
+        [
+            "-y",
+            "mcp-remote",
+            "https://docs.dev/sse",
+            "--transport",
+            "sse-only",
+            "--header",
+            "X-Trace:1",
+            "--header",
+            "Authorization:Bearer sk-abcdefghijklmnop",
+        ]
+    );
+    assert!(bridged.api_key.is_none());
+    assert!(!bridged.extra.contains_key("headers"));
+
+    let redacted = conformed.redacted();
+    assert_eq!(redacted.servers[0].args[6], "X-Trace:1");
+    assert_eq!(redacted.servers[0].args[8], "Authorization:Bear****");
+}
+
+#[test]
+fn api_key_on_a_stdio_server_is_reported_when_dropped() {
+    let settings = McpSettings {
+        servers: vec![McpServer {
+            id: "fs".to_string(),
+            transport: McpTransport::Stdio,
+            endpoint: String::new(),
+            command: Some("npx".to_string()),
+            api_key: Some("sk-abcdefghijklmnop".to_string()),
+            ..McpServer::default()
+        }],
+        ..Default::default()
+    };
+
+    let (conformed, warnings) = conform_settings(&settings, AgentKind::Cursor);
+    assert_eq!(
+        warnings,
+        ["fs: api_key is not supported for stdio servers, dropped; use env instead"]
+    );
+    assert!(conformed.servers[0].api_key.is_none());
+}
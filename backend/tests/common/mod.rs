@@ -0,0 +1,51 @@
+//! 통합 테스트가 함께 쓰는 임시 디렉터리와 데이터베이스 준비 코드입니다.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use backend::db::Database;
+
+/// 테스트마다 따로 쓰는 임시 디렉터리. 값이 사라질 때 디렉터리도 지웁니다.
+pub struct Scratch {
+    dir: PathBuf,
+}
+
+impl Scratch {
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("mcp-sync-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Self { dir }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn join(&self, relative: impl AsRef<Path>) -> PathBuf {
+        self.dir.join(relative)
+    }
+
+    /// 상위 디렉터리를 만들고 파일을 쓴 뒤 그 경로를 반환합니다.
+    pub fn write(&self, relative: impl AsRef<Path>, content: &str) -> PathBuf {
+        let path = self.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    pub fn db_path(&self) -> PathBuf {
+        self.join("mcp_sync.db")
+    }
+
+    pub fn database(&self) -> Database {
+        Database::initialize(&self.db_path()).unwrap()
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
@@ -0,0 +1,142 @@
+use backend::adapter::AgentKind;
+use backend::config::{McpServer, McpSettings, ProjectOverride, SyncPolicy, ToolConfiguration};
+use backend::diff::{diff_settings, diff_tool, FieldChange, ProjectOverrideChange, ServerChange};
+use backend::sync::merge_settings;
+use serde_json::{json, Map};
+
+fn server(id: &str, endpoint: &str) -> McpServer {
+    McpServer {
+        id: id.to_string(),
+        name: id.to_string(),
+        endpoint: endpoint.to_string(),
+        ..McpServer::default()
+    }
+}
+
+fn project(name: &str, server_id: &str) -> ProjectOverride {
+    ProjectOverride {
+        project: name.to_string(),
+        server_id: server_id.to_string(),
+        last_synced_at: None,
+        extra: Map::new(),
+    }
+}
+
+#[test]
+fn reports_added_removed_and_changed_fields() {
+    let master = McpSettings {
+        servers: vec![server("a", "https://a.dev"), server("b", "https://b2.dev")],
+        project_overrides: vec![project("web", "a"), project("api", "b")],
+        ..Default::default()
+    };
+    let mut changed = server("b", "https://b.dev");
+    changed.enabled = false;
+    changed.extra.insert("timeout".to_string(), json!(30));
+    let tool = McpSettings {
+        servers: vec![changed, server("c", "https://c.dev")],
+        project_overrides: vec![project("api", "c"), project("docs", "c")],
+        ..Default::default()
+    };
+
+    let diff = diff_settings(&master, &tool);
+    assert_eq!(
+        diff.servers,
+        [
+            ServerChange::Added {
+                id: "a".to_string(),
+                server: server("a", "https://a.dev"),
+            },
+            ServerChange::Modified {
+                id: "b".to_string(),
+                fields: vec![
+                    FieldChange {
+                        field: "endpoint".to_string(),
+                        master: json!("https://b2.dev"),
+                        tool: json!("https://b.dev"),
+                    },
+                    FieldChange {
+                        field: "enabled".to_string(),
+                        master: json!(true),
+                        tool: json!(false),
+                    },
+                    FieldChange {
+                        field: "timeout".to_string(),
+                        master: json!(null),
+                        tool: json!(30),
+                    },
+                ],
+            },
+            ServerChange::Removed {
+                id: "c".to_string(),
+                server: server("c", "https://c.dev"),
+            },
+        ]
+    );
+    assert_eq!(
+        diff.project_overrides,
+        [
+            ProjectOverrideChange::Added {
+                project: "web".to_string(),
+                entry: project("web", "a"),
+            },
+            ProjectOverrideChange::Modified {
+                project: "api".to_string(),
+                fields: vec![FieldChange {
+                    field: "server_id".to_string(),
+                    master: json!("b"),
+                    tool: json!("c"),
+                }],
+            },
+            ProjectOverrideChange::Removed {
+                project: "docs".to_string(),
+                entry: project("docs", "c"),
+            },
+        ]
+    );
+
+    assert!(diff_settings(&master, &master).is_empty());
+}
+
+#[test]
+fn tool_only_servers_kept_by_policy_are_not_removals() {
+    let master = McpSettings {
+        servers: vec![server("shared", "https://master.dev")],
+        ..Default::default()
+    };
+    let local = McpSettings {
+        servers: vec![
+            server("shared", "https://master.dev"),
+            server("local-only", "https://local.dev"),
+        ],
+        ..Default::default()
+    };
+    let adapter = AgentKind::Cursor.adapter();
+    // 방금 동기화한 상태: 병합 결과를 파일에 쓰고 다시 읽은 것이 도구 구성입니다.
+    let synced = |policy| {
+        let rendered = adapter
+            .render(&merge_settings(&master, &local, policy), None)
+            .unwrap();
+        let mut tool = ToolConfiguration::new(
+            "cursor",
+            AgentKind::Cursor,
+            "/nonexistent/mcp.json",
+            adapter.parse(&rendered).unwrap(),
+        );
+        tool.policy = policy;
+        tool
+    };
+
+    for policy in [SyncPolicy::Additive, SyncPolicy::PreserveLocal] {
+        let diff = diff_tool(&synced(policy), &master).unwrap();
+        assert!(diff.in_sync, "{policy}: {:?}", diff.changes);
+    }
+
+    let mut tool = synced(SyncPolicy::Additive);
+    tool.policy = SyncPolicy::Mirror;
+    let diff = diff_tool(&tool, &master).unwrap();
+    assert!(!diff.in_sync);
+    assert!(matches!(
+        &diff.changes.servers[..],
+        [ServerChange::Removed { id, .. }] if id == "local-only"
+    ));
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use std::fs;
+
+use backend::adapter::AgentKind;
+use backend::db::{Database, ToolRecord};
+use backend::discovery::{discover_installed, discover_projects, DiscoveryEnv};
+use backend::settings::Settings;
+use backend::sync;
+use common::Scratch;
+
+#[test]
+fn finds_agents_by_config_file_or_install_marker() {
+    let scratch = Scratch::new("discovery");
+    let home = scratch.path();
+    fs::create_dir_all(home.join(".cursor")).unwrap();
+    fs::write(home.join(".cursor/mcp.json"), "{\"mcpServers\": {}}").unwrap();
+    fs::create_dir_all(home.join(".gemini")).unwrap();
+
+    let mut env = DiscoveryEnv::new(home);
+    env.xdg_config_home = home.join("xdg");
+    fs::create_dir_all(env.xdg_config_home.join("Claude")).unwrap();
+
+    let found = discover_installed(&env);
+    let summary: Vec<_> = found
+        .iter()
+        .map(|tool| (tool.name.as_str(), tool.kind, tool.config_path.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                "claude-desktop",
+                AgentKind::ClaudeDesktop,
+                home.join("xdg/Claude/claude_desktop_config.json")
+            ),
+            ("cursor", AgentKind::Cursor, home.join(".cursor/mcp.json")),
+            (
+                "gemini-cli",
+                AgentKind::GeminiCli,
+                home.join(".gemini/settings.json")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn empty_home_finds_nothing() {
+    let scratch = Scratch::new("empty");
+    let home = scratch.path();
+    assert!(discover_installed(&DiscoveryEnv::new(home)).is_empty());
+}
+
+#[test]
+fn scans_config_root_into_database_at_configured_path() {
+    let scratch = Scratch::new("settings");
+    let dir = scratch.path();
+    let root = dir.join("tool_configs");
+    fs::create_dir_all(root.join("sample")).unwrap();
+    fs::write(root.join("sample/mcp.json"), "{\"servers\": []}").unwrap();
+
+    let settings = Settings {
+        db_path: dir.join("data/mcp_sync.db"),
+        config_root: Some(root.clone()),
+        discovery: Some(DiscoveryEnv::new(dir.join("home"))),
+        admin_token: None,
+    };
+    let db = Database::initialize(&settings.db_path).unwrap();
+    let found = sync::discover_tools(&db, &settings).unwrap();
+
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "sample");
+    assert!(settings.db_path.is_file());
+    assert_eq!(
+        db.find_tool("sample").unwrap(),
+        Some(ToolRecord::new("sample", root.join("sample/mcp.json")))
+    );
+}
+
+#[test]
+fn registers_project_cursor_config_for_path_projects() {
+    let scratch = Scratch::new("projects");
+    let home = scratch.path();
+    let project = home.join("work/app");
+    fs::create_dir_all(project.join(".cursor")).unwrap();
+    let env = DiscoveryEnv::new(home);
+
+    let found = discover_projects(
+        Some(&env),
+        &["~/work/app", "internal-tools", "/nonexistent"],
+    );
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "cursor:~/work/app");
+    assert_eq!(found[0].agent, "cursor");
+    assert_eq!(found[0].kind, AgentKind::Cursor);
+    assert_eq!(found[0].config_path, project.join(".cursor/mcp.json"));
+    assert_eq!(found[0].project.as_deref(), Some("~/work/app"));
+}
+
+#[test]
+fn keeps_the_discovered_kind_for_loading() {
+    let scratch = Scratch::new("kind");
+    let dir = scratch.path();
+    let home = dir.join("home");
+    fs::create_dir_all(home.join(".config/zed")).unwrap();
+    // Zed 설정에 `mcpServers` 가 있어도 내용 시그니처 대신 검색된 형식으로 읽습니다.
+    fs::write(
+        home.join(".config/zed/settings.json"),
+        "{\"mcpServers\": {\"fs\": {\"command\": \"npx\"}}}",
+    )
+    .unwrap();
+    let settings = Settings {
+        db_path: dir.join("mcp_sync.db"),
+        config_root: None,
+        discovery: Some(DiscoveryEnv::new(home)),
+        admin_token: None,
+    };
+    let db = Database::initialize(&settings.db_path).unwrap();
+    sync::discover_tools(&db, &settings).unwrap();
+
+    let record = db.find_tool("zed").unwrap().unwrap();
+    assert_eq!(record.kind, Some(AgentKind::Zed));
+    let tool = sync::load_tool(&record, &db.load_secrets().unwrap()).unwrap();
+    assert_eq!(tool.kind, AgentKind::Zed);
+    assert!(tool.settings.servers.is_empty());
+
+    // 형식을 모르는 도구는 이전처럼 이름과 내용으로 고릅니다.
+    let unknown = ToolRecord {
+        kind: None,
+        ..record.clone()
+    };
+    let tool = sync::load_tool(&unknown, &db.load_secrets().unwrap()).unwrap();
+    assert_eq!(tool.kind, AgentKind::ClaudeDesktop);
+}
+
+#[test]
+fn config_root_tool_does_not_overwrite_a_home_tool_with_the_same_name() {
+    let dir = Scratch::new("root-collision");
+    let root_config = dir.write("tool_configs/cursor/mcp.json", "{\"servers\": []}");
+    let home_config = dir.write("home/.cursor/mcp.json", "{\"mcpServers\": {}}");
+    let settings = Settings {
+        db_path: dir.db_path(),
+        config_root: Some(dir.join("tool_configs")),
+        discovery: Some(DiscoveryEnv::new(dir.join("home"))),
+        admin_token: None,
+    };
+    let db = dir.database();
+    sync::discover_tools(&db, &settings).unwrap();
+    sync::discover_tools(&db, &settings).unwrap();
+
+    let tools: Vec<_> = db
+        .list_tools()
+        .unwrap()
+        .into_iter()
+        .map(|tool| (tool.name, tool.agent, tool.config_path))
+        .collect();
+    assert_eq!(
+        tools,
+        vec![
+            ("cursor".to_string(), "cursor".to_string(), home_config),
+            ("root:cursor".to_string(), "cursor".to_string(), root_config),
+        ]
+    );
+}
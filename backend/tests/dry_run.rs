@@ -0,0 +1,120 @@
+mod common;
+
+use std::fs;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use backend::adapter::AgentKind;
+use backend::api::{router, AppState};
+use backend::config::{McpServer, McpSettings, RevisionMeta, RevisionSource};
+use backend::db::{Database, ToolRecord};
+use backend::secrets::Secrets;
+use backend::settings::{Settings, SettingsOverrides};
+use backend::sync::{load_tool, plan_tool};
+use common::Scratch;
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+const DESKTOP_CONFIG: &str = "{\"mcpServers\": {\"local\": {\"command\": \"uvx\"}}}\n";
+const CURSOR_CONFIG: &str = "{\"mcpServers\": {\"local\": {\"command\": \"uvx\"}}}\n";
+
+#[test]
+fn dry_run_plans_without_writing_files() {
+    let dir = Scratch::new("plan");
+    let path = dir.write("claude-desktop/claude_desktop_config.json", DESKTOP_CONFIG);
+    let master = McpSettings {
+        servers: vec![McpServer {
+            id: "docs".to_string(),
+            name: "docs".to_string(),
+            endpoint: "https://docs.dev/mcp".to_string(),
+            ..McpServer::default()
+        }],
+        ..Default::default()
+    };
+
+    let tool = load_tool(
+        &ToolRecord::new("claude-desktop", &path),
+        &Secrets::default(),
+    )
+    .unwrap();
+    assert_eq!(tool.kind, AgentKind::ClaudeDesktop);
+    let plan = plan_tool(&tool, &master, None, &Secrets::default()).unwrap();
+    assert!(plan.changed);
+    assert!(plan.proposed.contains("https://docs.dev/mcp"));
+    assert_eq!(
+        serde_json::to_value(&plan.changes).unwrap()["servers"][0]["change"],
+        json!("added")
+    );
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), DESKTOP_CONFIG);
+    assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+}
+
+#[tokio::test]
+async fn dry_run_plans_without_writing_files_or_history() {
+    let dir = Scratch::new("dry-run");
+    let db_path = dir.join("mcp_sync.db");
+    let db = Arc::new(Database::initialize(&db_path).unwrap());
+    db.upsert_master_config(
+        &McpSettings {
+            servers: vec![McpServer {
+                id: "docs".to_string(),
+                name: "docs".to_string(),
+                endpoint: "https://docs.dev/mcp".to_string(),
+                ..McpServer::default()
+            }],
+            ..Default::default()
+        },
+        &RevisionMeta::new(RevisionSource::Cli, None),
+    )
+    .unwrap();
+    let path = dir.write("cursor/mcp.json", CURSOR_CONFIG);
+    db.record_tool_detection(&ToolRecord::new("cursor", &path))
+        .unwrap();
+    // 읽을 수 없는 도구가 있어도 나머지 도구의 계획은 돌려줍니다.
+    let broken = dir.write("windsurf/mcp_config.json", "{ not json");
+    db.record_tool_detection(&ToolRecord::new("windsurf", &broken))
+        .unwrap();
+    let settings = Settings::load(SettingsOverrides {
+        db_path: Some(db_path),
+        config_root: None,
+    })
+    .unwrap();
+    let app = router(AppState {
+        db: db.clone(),
+        settings: Arc::new(settings),
+    });
+
+    let response = app
+        .oneshot(
+            Request::post("/api/sync")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({ "dry_run": true }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let plans: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(plans.as_array().unwrap().len(), 2);
+    assert!(plans[0].get("error").is_none());
+    assert_eq!(plans[1]["tool"], json!("windsurf"));
+    assert!(plans[1]["error"].as_str().is_some());
+    assert_eq!(plans[0]["tool"], json!("cursor"));
+    assert_eq!(plans[0]["changed"], json!(true));
+    assert!(plans[0]["proposed"]
+        .as_str()
+        .unwrap()
+        .contains("https://docs.dev/mcp"));
+    assert_eq!(plans[0]["changes"]["servers"][0]["change"], json!("added"));
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), CURSOR_CONFIG);
+    assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+    assert!(db.recent_sync_history(10).unwrap().is_empty());
+    assert!(db.find_sync_base("cursor").unwrap().is_none());
+    assert!(db.find_backup("cursor", None).unwrap().is_none());
+    assert_eq!(fs::read_to_string(&broken).unwrap(), "{ not json");
+}
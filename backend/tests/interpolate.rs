@@ -0,0 +1,65 @@
+use backend::config::{McpServer, McpSettings};
+use backend::interpolate::{expand_with, unexpand_previous, unexpand_with};
+
+fn settings_with(endpoint: &str, args: &[&str], home: &str) -> McpSettings {
+    let mut server = McpServer {
+        id: "files".to_string(),
+        endpoint: endpoint.to_string(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        ..McpServer::default()
+    };
+    server
+        .env
+        .insert("FILES_HOME".to_string(), home.to_string());
+    McpSettings {
+        servers: vec![server],
+        ..Default::default()
+    }
+}
+
+fn lookup(name: &str) -> Option<String> {
+    match name {
+        "PORT" => Some("7000".to_string()),
+        "HOME" => Some("/home/dev".to_string()),
+        "EMPTY" => Some(String::new()),
+        _ => None,
+    }
+}
+
+#[test]
+fn env_references_expand_and_compare_unexpanded() {
+    let master = settings_with(
+        "http://localhost:${env:PORT}/mcp",
+        &["--root", "${env:HOME}/projects", "--mode=${env:EMPTY:-ro}"],
+        "${env:FILES_HOME:-/srv/files}",
+    );
+    let expanded = expand_with(&master, lookup).unwrap();
+    assert_eq!(
+        expanded,
+        settings_with(
+            "http://localhost:7000/mcp",
+            &["--root", "/home/dev/projects", "--mode=ro"],
+            "/srv/files",
+        )
+    );
+    assert_eq!(unexpand_with(&expanded, &master, lookup), master);
+
+    // 값이 달라진 항목만 차이로 남습니다.
+    let mut edited = expanded.clone();
+    edited.servers[0].endpoint = "http://localhost:9000/mcp".to_string();
+    let restored = unexpand_with(&edited, &master, lookup);
+    assert_eq!(restored.servers[0].endpoint, "http://localhost:9000/mcp");
+    assert_eq!(restored.servers[0].args, master.servers[0].args);
+
+    // 지난번에 풀어 기록한 값 그대로인 항목만 참조로 되돌립니다.
+    let stale = expand_with(&master, |_| Some("old".to_string())).unwrap();
+    assert_eq!(unexpand_previous(&stale, &master, &stale), master);
+    let mut renamed = stale.clone();
+    renamed.servers[0].endpoint = "http://localhost:old/v2".to_string();
+    let restored = unexpand_previous(&renamed, &master, &stale);
+    assert_eq!(restored.servers[0].endpoint, "http://localhost:old/v2");
+    assert_eq!(restored.servers[0].args, master.servers[0].args);
+
+    let missing = settings_with("${env:MISSING}", &[], "");
+    assert!(expand_with(&missing, lookup).is_err());
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use backend::db::{Database, ToolRecord};
+use common::Scratch;
+
+#[test]
+fn imports_database_from_the_legacy_default_location() {
+    let dir = Scratch::new("legacy");
+    let legacy = dir.join("database/mcp_sync.db");
+    let db = Database::initialize(&legacy).unwrap();
+    db.record_tool_detection(&ToolRecord::new("legacy-tool", dir.join("mcp.json")))
+        .unwrap();
+    drop(db);
+    // 예전 기본 경로는 `backend/` 에서 실행할 때의 `../database` 입니다.
+    let workdir = dir.join("backend");
+    fs::create_dir_all(&workdir).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_agentctl"))
+        .arg("list")
+        .current_dir(&workdir)
+        .env("HOME", dir.join("home"))
+        .env_remove("XDG_DATA_HOME")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("MCP_SYNC_DB")
+        .env_remove("MCP_SYNC_CONFIG_ROOT")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("legacy-tool"));
+
+    let imported = dir.join("home/.local/share/ai-mcp-sync/mcp_sync.db");
+    assert!(imported.is_file());
+    assert!(legacy.is_file());
+}
+
+#[test]
+fn scans_the_legacy_tool_configs_directory_by_default() {
+    let dir = Scratch::new("legacy-root");
+    dir.write("tool_configs/sample/mcp.json", "{\"servers\": []}");
+    let workdir = dir.join("backend");
+    fs::create_dir_all(&workdir).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_agentctl"))
+        .args(["--db", dir.db_path().to_str().unwrap(), "scan"])
+        .current_dir(&workdir)
+        .env("HOME", dir.join("home"))
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("MCP_SYNC_CONFIG_ROOT")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let record = dir.database().find_tool("sample").unwrap().unwrap();
+    assert_eq!(
+        record.config_path,
+        Path::new("../tool_configs/sample/mcp.json")
+    );
+}
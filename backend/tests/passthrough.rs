@@ -0,0 +1,491 @@
+use std::path::Path;
+
+use backend::adapter::AgentKind;
+use backend::config::{McpServer, McpSettings, SyncPolicy};
+use backend::sync::merge_settings;
+use serde_json::json;
+
+const NATIVE_CONFIG: &str = r#"{
+  "servers": [
+    {
+      "id": "beta",
+      "name": "Beta MCP",
+      "description": "Staging environment",
+      "transport": "streamable_http",
+      "endpoint": "https://beta.mcp.dev",
+      "api_key": null,
+      "enabled": true,
+      "alwaysAllow": [
+        "read_file"
+      ],
+      "timeout": 30
+    }
+  ],
+  "project_overrides": [
+    {
+      "project": "internal-tools",
+      "server_id": "beta",
+      "last_synced_at": null,
+      "pinned": true
+    }
+  ],
+  "vendor": {
+    "theme": "dark"
+  }
+}"#;
+
+const CLAUDE_CONFIG: &str = r#"{
+  "globalShortcut": "Ctrl+Space",
+  "mcpServers": {
+    "filesystem": {
+      "command": "npx",
+      "args": [
+        "-y",
+        "@modelcontextprotocol/server-filesystem"
+      ],
+      "disabled": false,
+      "alwaysAllow": [
+        "list_directory"
+      ]
+    }
+  },
+  "preferences": {
+    "telemetry": false,
+    "recentProjects": [
+      "a",
+      "b"
+    ]
+  }
+}
+"#;
+
+#[test]
+fn native_round_trip_is_byte_identical() {
+    let adapter = AgentKind::Native.adapter();
+    let tool = adapter.parse(NATIVE_CONFIG).unwrap();
+    let merged = merge_settings(&tool, &tool, SyncPolicy::Additive);
+    let rendered = adapter.render(&merged, Some(NATIVE_CONFIG)).unwrap();
+    assert_eq!(rendered, NATIVE_CONFIG);
+}
+
+#[test]
+fn native_merge_keeps_vendor_keys_when_master_wins() {
+    let adapter = AgentKind::Native.adapter();
+    let tool = adapter.parse(NATIVE_CONFIG).unwrap();
+    let mut master = tool.clone();
+    master.servers[0].endpoint = "https://beta2.mcp.dev".to_string();
+    master.servers[0].extra.clear();
+    master.project_overrides[0].extra.clear();
+    master.extra.clear();
+
+    let merged = merge_settings(&master, &tool, SyncPolicy::Additive);
+    let rendered = adapter.render(&merged, Some(NATIVE_CONFIG)).unwrap();
+    let expected = NATIVE_CONFIG.replace("https://beta.mcp.dev", "https://beta2.mcp.dev");
+    assert_eq!(rendered, expected);
+}
+
+#[test]
+fn claude_render_preserves_unrelated_content() {
+    let adapter = AgentKind::ClaudeDesktop.adapter();
+    let tool = adapter.parse(CLAUDE_CONFIG).unwrap();
+    assert_eq!(
+        tool.servers[0].extra["alwaysAllow"],
+        json!(["list_directory"])
+    );
+
+    let master = McpSettings {
+        servers: vec![McpServer::default()],
+        ..Default::default()
+    };
+    let merged = merge_settings(&master, &tool, SyncPolicy::Additive);
+    let rendered = adapter.render(&merged, Some(CLAUDE_CONFIG)).unwrap();
+
+    assert!(rendered.starts_with("{\n  \"globalShortcut\": \"Ctrl+Space\",\n  \"mcpServers\": {"));
+    let preferences = &CLAUDE_CONFIG[CLAUDE_CONFIG.find("  \"preferences\"").unwrap()..];
+    assert!(rendered.ends_with(preferences));
+    let filesystem = r#"    "filesystem": {
+      "command": "npx",
+      "args": [
+        "-y",
+        "@modelcontextprotocol/server-filesystem"
+      ],
+      "disabled": false,
+      "alwaysAllow": [
+        "list_directory"
+      ]
+    }"#;
+    assert!(rendered.contains(filesystem));
+    assert!(rendered.contains("\"default\": {\n      \"url\": \"http://localhost:3001\"\n    }"));
+}
+
+const VSCODE_MCP_CONFIG: &str = r#"{
+	// prompted secrets
+	"inputs": [
+		{ "type": "promptString", "id": "token", "password": true },
+	],
+	"servers": {
+		"github": { "type": "http", "url": "https://api.githubcopilot.com/mcp/" },
+	},
+}
+"#;
+
+#[test]
+fn vscode_render_replaces_only_the_server_map() {
+    let adapter = AgentKind::VsCode.adapter();
+    assert!(adapter.detect(VSCODE_MCP_CONFIG));
+    let tool = adapter.parse(VSCODE_MCP_CONFIG).unwrap();
+    assert_eq!(
+        tool.servers[0].endpoint,
+        "https://api.githubcopilot.com/mcp/"
+    );
+
+    let mut master = tool.clone();
+    master.servers[0].endpoint = "https://example.com/mcp".to_string();
+    let rendered = adapter.render(&master, Some(VSCODE_MCP_CONFIG)).unwrap();
+
+    let servers_at = VSCODE_MCP_CONFIG.find("\t\"servers\"").unwrap();
+    assert!(rendered.starts_with(&VSCODE_MCP_CONFIG[..servers_at]));
+    assert!(rendered.ends_with("\t},\n}\n"));
+    assert!(rendered.contains(
+        "\t\"servers\": {\n\t\t\"github\": {\n\t\t\t\"type\": \"http\",\n\t\t\t\"url\": \"https://example.com/mcp\"\n\t\t}\n\t},"
+    ));
+}
+
+#[test]
+fn vscode_settings_gain_mcp_section_without_touching_comments() {
+    let settings = "{\n    // editor\n    \"editor.fontSize\": 14,\n}\n";
+    let adapter = AgentKind::VsCode.adapter_for(Path::new("Code/User/settings.json"));
+    assert!(adapter.parse(settings).unwrap().servers.is_empty());
+
+    let master = McpSettings {
+        servers: vec![McpServer::default()],
+        ..Default::default()
+    };
+    let rendered = adapter.render(&master, Some(settings)).unwrap();
+    assert_eq!(
+        rendered,
+        "{\n    // editor\n    \"editor.fontSize\": 14,\n    \"mcp\": {\n        \"servers\": {\n            \"default\": {\n                \"type\": \"http\",\n                \"url\": \"http://localhost:3001\"\n            }\n        }\n    },\n}\n"
+    );
+    assert_eq!(adapter.parse(&rendered).unwrap().servers.len(), 1);
+}
+
+#[test]
+fn vscode_mcp_file_with_only_inputs_keeps_top_level_servers() {
+    let mcp = "{\n  \"inputs\": []\n}\n";
+    let adapter = AgentKind::VsCode.adapter_for(Path::new(".vscode/mcp.json"));
+    assert!(adapter.parse(mcp).unwrap().servers.is_empty());
+
+    let master = McpSettings {
+        servers: vec![McpServer::default()],
+        ..Default::default()
+    };
+    let rendered = adapter.render(&master, Some(mcp)).unwrap();
+    assert_eq!(
+        rendered,
+        "{\n  \"inputs\": [],\n  \"servers\": {\n    \"default\": {\n      \"type\": \"http\",\n      \"url\": \"http://localhost:3001\"\n    }\n  }\n}\n"
+    );
+    // 새 settings.json 에는 `mcp.servers` 로 기록합니다.
+    let settings = AgentKind::VsCode.adapter_for(Path::new("settings.json"));
+    let created = settings.render(&master, None).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&created).unwrap()["mcp"]["servers"]["default"]
+            ["url"],
+        "http://localhost:3001"
+    );
+}
+
+const CODEX_CONFIG: &str = r#"# Codex settings
+model = "o3"  # default model
+
+[mcp_servers.docs]
+command = "npx" # runner
+args = ["-y", "docs-mcp"]
+env = { "API_KEY" = "x" }
+startup_timeout_sec = 20
+
+[profiles.fast]
+model = "o4-mini"
+"#;
+
+#[test]
+fn codex_render_edits_only_changed_keys() {
+    let adapter = AgentKind::Codex.adapter();
+    assert!(adapter.detect(CODEX_CONFIG));
+    let tool = adapter.parse(CODEX_CONFIG).unwrap();
+    assert_eq!(tool.servers[0].command.as_deref(), Some("npx"));
+    assert_eq!(tool.servers[0].env["API_KEY"], "x");
+    assert_eq!(tool.servers[0].extra["startup_timeout_sec"], json!(20));
+    assert_eq!(
+        adapter.render(&tool, Some(CODEX_CONFIG)).unwrap(),
+        CODEX_CONFIG
+    );
+
+    let mut master = tool.clone();
+    master.servers[0].args[1] = "docs-mcp@2".to_string();
+    master.servers.push(McpServer::default());
+    let rendered = adapter.render(&master, Some(CODEX_CONFIG)).unwrap();
+    let expected = CODEX_CONFIG
+        .replace("\"docs-mcp\"", "\"docs-mcp@2\"")
+        .replace(
+            "[profiles.fast]",
+            "[mcp_servers.default]\nurl = \"http://localhost:3001\"\n\n[profiles.fast]",
+        );
+    assert_eq!(rendered, expected);
+}
+
+const CONTINUE_CONFIG: &str = "name: Local Assistant
+models:
+  - name: Claude
+    provider: anthropic   # keep me
+
+mcpServers:
+  - name: sqlite
+    command: npx
+    args:
+    - mcp-sqlite
+    connectionTimeout: 30
+
+# rules section
+rules:
+  - Be concise
+";
+
+#[test]
+fn continue_render_replaces_only_the_server_block() {
+    let adapter = AgentKind::Continue.adapter();
+    assert!(adapter.detect(CONTINUE_CONFIG));
+    let tool = adapter.parse(CONTINUE_CONFIG).unwrap();
+    assert_eq!(tool.servers[0].id, "sqlite");
+    assert_eq!(tool.servers[0].extra["connectionTimeout"], json!(30));
+
+    let mut master = tool.clone();
+    master.servers.push(McpServer::default());
+    let rendered = adapter.render(&master, Some(CONTINUE_CONFIG)).unwrap();
+    let expected = CONTINUE_CONFIG.replace(
+        "    connectionTimeout: 30\n",
+        "    connectionTimeout: 30\n  - name: default\n    type: streamable-http\n    url: http://localhost:3001\n",
+    );
+    assert_eq!(rendered, expected);
+    assert_eq!(adapter.parse(&rendered).unwrap().servers.len(), 2);
+}
+
+const CONTINUE_COMMENTED: &str = "mcpServers: # synced
+  # local database
+  - name: sqlite
+    command: npx # runner
+    args:
+    - mcp-sqlite
+  # docs server
+  - name: docs
+    command: uvx
+    args: [docs-mcp] # pinned
+";
+
+#[test]
+fn continue_keeps_comments_of_unchanged_items() {
+    let adapter = AgentKind::Continue.adapter();
+    let mut master = adapter.parse(CONTINUE_COMMENTED).unwrap();
+    master.servers[1].args = vec!["docs-mcp@2".to_string()];
+    let rendered = adapter.render(&master, Some(CONTINUE_COMMENTED)).unwrap();
+    // 바뀐 항목은 다시 쓰므로 그 항목 안의 주석(`# pinned`)만 사라집니다.
+    assert_eq!(
+        rendered,
+        "mcpServers: # synced
+  # local database
+  - name: sqlite
+    command: npx # runner
+    args:
+    - mcp-sqlite
+  # docs server
+  - name: docs
+    command: uvx
+    args:
+    - docs-mcp@2
+"
+    );
+
+    master.servers.remove(0);
+    let rendered = adapter.render(&master, Some(&rendered)).unwrap();
+    assert!(rendered.starts_with("mcpServers: # synced\n  # docs server\n  - name: docs\n"));
+    assert!(!rendered.contains("sqlite"));
+}
+
+const ZED_SETTINGS: &str = r#"// Zed settings
+{
+  "theme": "One Dark",
+  "context_servers": {
+    "sqlite": {
+      "source": "custom",
+      "command": {
+        "path": "npx",
+        "args": ["mcp-sqlite"]
+      }
+    },
+    "postgres": {
+      "source": "extension",
+      "settings": { "database_url": "postgres://localhost/app" }
+    },
+    "flat": { "command": "uvx", "args": ["flat-mcp"] },
+  },
+  "vim_mode": true, // yes
+}
+"#;
+
+#[test]
+fn zed_maps_nested_command_and_keeps_entry_shape() {
+    let adapter = AgentKind::Zed.adapter();
+    assert!(adapter.detect(ZED_SETTINGS));
+    let tool = adapter.parse(ZED_SETTINGS).unwrap();
+    assert_eq!(tool.servers[0].command.as_deref(), Some("npx"));
+    assert_eq!(tool.servers[0].args, vec!["mcp-sqlite".to_string()]);
+    assert_eq!(tool.servers[0].extra["source"], json!("custom"));
+    assert_eq!(tool.servers[1].command.as_deref(), Some("uvx"));
+    // 확장 항목은 서버로 읽지 않습니다.
+    assert_eq!(tool.servers.len(), 2);
+
+    let mut master = tool.clone();
+    master.servers[0]
+        .env
+        .insert("DB".to_string(), "/tmp/x.db".to_string());
+    let rendered = adapter.render(&master, Some(ZED_SETTINGS)).unwrap();
+    assert!(rendered.starts_with("// Zed settings\n{\n  \"theme\": \"One Dark\",\n"));
+    assert!(rendered.ends_with("  },\n  \"vim_mode\": true, // yes\n}\n"));
+    let reparsed = adapter.parse(&rendered).unwrap();
+    assert_eq!(reparsed, master);
+    assert!(rendered.contains(
+        "\"command\": {\n        \"path\": \"npx\",\n        \"args\": [\n          \"mcp-sqlite\"\n        ],\n        \"env\": {\n          \"DB\": \"/tmp/x.db\"\n        }\n      }"
+    ));
+    assert!(rendered.contains("\"flat\": {\n      \"command\": \"uvx\","));
+    // 확장 항목은 원래 자리에 그대로 남습니다.
+    assert!(rendered.contains(
+        "    },\n    \"postgres\": {\n      \"source\": \"extension\",\n      \"settings\": {\n        \"database_url\": \"postgres://localhost/app\"\n      }\n    },\n    \"flat\": {"
+    ));
+
+    master.servers.clear();
+    let emptied = adapter.render(&master, Some(&rendered)).unwrap();
+    assert!(emptied.contains("\"postgres\": {"));
+    assert!(adapter.parse(&emptied).unwrap().servers.is_empty());
+}
+
+const CURSOR_REMOTE: &str = r#"{
+  "mcpServers": {
+    "github": {
+      "url": "https://api.githubcopilot.com/mcp/",
+      "headers": {
+        "Authorization": "Bearer ghp_local",
+        "X-Trace": "1"
+      }
+    }
+  }
+}"#;
+
+#[test]
+fn remote_api_key_maps_to_bearer_header() {
+    let adapter = AgentKind::Cursor.adapter();
+    let tool = adapter.parse(CURSOR_REMOTE).unwrap();
+    assert_eq!(tool.servers[0].api_key.as_deref(), Some("ghp_local"));
+    assert_eq!(tool.servers[0].extra["headers"], json!({"X-Trace": "1"}));
+    assert_eq!(
+        adapter.render(&tool, Some(CURSOR_REMOTE)).unwrap(),
+        CURSOR_REMOTE
+    );
+
+    let mut master = tool.clone();
+    master.servers[0].api_key = None;
+    let rendered = adapter.render(&master, Some(CURSOR_REMOTE)).unwrap();
+    assert!(!rendered.contains("Authorization"));
+    assert!(rendered.contains("\"X-Trace\": \"1\""));
+
+    let codex = AgentKind::Codex.adapter();
+    let mut master = McpSettings {
+        servers: vec![McpServer::default()],
+        ..Default::default()
+    };
+    master.servers[0].api_key = Some("sk-remote".to_string());
+    let rendered = codex.render(&master, None).unwrap();
+    assert!(rendered.contains("Authorization = \"Bearer sk-remote\""));
+    assert_eq!(
+        codex.parse(&rendered).unwrap().servers[0]
+            .api_key
+            .as_deref(),
+        Some("sk-remote")
+    );
+}
+
+#[test]
+fn disabled_key_maps_to_enabled() {
+    let adapter = AgentKind::ClaudeDesktop.adapter();
+    let tool = adapter.parse(CLAUDE_CONFIG).unwrap();
+    assert!(tool.servers[0].enabled);
+    assert!(!tool.servers[0].extra.contains_key("disabled"));
+
+    let mut master = tool.clone();
+    master.servers[0].enabled = false;
+    let rendered = adapter.render(&master, Some(CLAUDE_CONFIG)).unwrap();
+    assert_eq!(
+        rendered,
+        CLAUDE_CONFIG.replace("\"disabled\": false", "\"disabled\": true")
+    );
+    assert!(!adapter.parse(&rendered).unwrap().servers[0].enabled);
+    assert_eq!(
+        adapter.render(&tool, Some(&rendered)).unwrap(),
+        CLAUDE_CONFIG
+    );
+}
+
+const CLAUDE_SCALARS: &str = r#"{
+  "mcpServers": {
+    "postgres": {
+      "command": "pg-mcp",
+      "args": [
+        "--port",
+        5432,
+        "--readonly",
+        true
+      ],
+      "env": {
+        "PORT": 3000,
+        "DEBUG": true,
+        "HOST": "db.local"
+      }
+    }
+  }
+}
+"#;
+
+const CODEX_SCALARS: &str = r#"[mcp_servers.postgres]
+command = "pg-mcp"
+args = ["--port", 5432]
+env = { PORT = 3000, DEBUG = true }
+"#;
+
+#[test]
+fn non_string_args_and_env_survive_a_no_op_round_trip() {
+    let adapter = AgentKind::ClaudeDesktop.adapter();
+    let tool = adapter.parse(CLAUDE_SCALARS).unwrap();
+    let server = &tool.servers[0];
+    assert_eq!(server.args, ["--port", "5432", "--readonly", "true"]);
+    assert_eq!(server.env["PORT"], "3000");
+    assert_eq!(server.env["DEBUG"], "true");
+    assert_eq!(
+        adapter.render(&tool, Some(CLAUDE_SCALARS)).unwrap(),
+        CLAUDE_SCALARS
+    );
+
+    let adapter = AgentKind::Codex.adapter();
+    let tool = adapter.parse(CODEX_SCALARS).unwrap();
+    assert_eq!(tool.servers[0].args, ["--port", "5432"]);
+    assert_eq!(tool.servers[0].env["PORT"], "3000");
+    assert_eq!(
+        adapter.render(&tool, Some(CODEX_SCALARS)).unwrap(),
+        CODEX_SCALARS
+    );
+
+    // 문자열로 바꿀 수 없는 값은 버리지 않고 거부합니다.
+    let nested = r#"{"mcpServers": {"x": {"command": "x", "env": {"A": {"b": 1}}}}}"#;
+    let err = AgentKind::ClaudeDesktop
+        .adapter()
+        .parse(nested)
+        .unwrap_err();
+    assert!(err.to_string().contains("server 'x'"), "{err}");
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use std::fs;
+
+use backend::config::{McpServer, McpSettings, RevisionMeta, RevisionSource, SyncStatus};
+use backend::db::ToolRecord;
+use backend::sync;
+use common::Scratch;
+
+fn settings_with(id: &str) -> McpSettings {
+    McpSettings {
+        servers: vec![McpServer {
+            id: id.to_string(),
+            endpoint: format!("https://{id}.dev"),
+            ..McpServer::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn tools_sync_from_their_assigned_profile() {
+    let dir = Scratch::new("profiles");
+    let db = dir.database();
+    db.upsert_master_config(
+        &settings_with("personal"),
+        &RevisionMeta::new(RevisionSource::Cli, None),
+    )
+    .unwrap();
+    let path = dir.write("cursor/mcp.json", "{\"mcpServers\": {}}");
+    db.record_tool_detection(&ToolRecord::new("cursor", &path))
+        .unwrap();
+
+    db.create_profile("work", &settings_with("jira")).unwrap();
+    assert!(db.create_profile("work", &McpSettings::default()).is_err());
+    assert!(db
+        .create_profile("default", &McpSettings::default())
+        .is_err());
+    assert!(db.set_tool_profile("cursor", "missing").is_err());
+    db.set_tool_profile("cursor", "work").unwrap();
+
+    let record = db.find_tool("cursor").unwrap().unwrap();
+    assert_eq!(record.profile.as_deref(), Some("work"));
+    let summary = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(summary.status, SyncStatus::Updated);
+    let written = fs::read_to_string(&path).unwrap();
+    assert!(written.contains("jira") && !written.contains("personal"));
+
+    let profiles = db.list_profiles().unwrap();
+    let names: Vec<_> = profiles
+        .iter()
+        .map(|profile| profile.name.as_str())
+        .collect();
+    assert_eq!(names, ["default", "work"]);
+    assert_eq!(profiles[1].tools, ["cursor"]);
+
+    assert!(db.remove_profile("work").is_err());
+    assert!(db.remove_profile("default").is_err());
+    db.set_tool_profile("cursor", "default").unwrap();
+    db.remove_profile("work").unwrap();
+    assert!(db.get_profile("work").unwrap().is_none());
+}
+
+#[test]
+fn named_profiles_keep_their_own_revisions() {
+    let dir = Scratch::new("profile-revisions");
+    let db = dir.database();
+    let meta = RevisionMeta::new(RevisionSource::Cli, None);
+    let master = db
+        .upsert_master_config(&settings_with("personal"), &meta)
+        .unwrap();
+    db.create_profile("work", &settings_with("jira")).unwrap();
+    db.save_profile("work", &settings_with("linear"), &meta)
+        .unwrap();
+    db.save_profile("work", &settings_with("notion"), &meta)
+        .unwrap();
+
+    let revisions = db.list_profile_revisions("work", 10).unwrap();
+    let ids: Vec<_> = revisions
+        .iter()
+        .map(|revision| revision.settings.servers[0].id.as_str())
+        .collect();
+    assert_eq!(ids, ["notion", "linear", "jira"]);
+    let master_ids: Vec<_> = db
+        .list_master_revisions(10)
+        .unwrap()
+        .iter()
+        .map(|revision| revision.id)
+        .collect();
+    assert_eq!(master_ids, [master]);
+    assert!(db.get_master_revision(revisions[0].id).unwrap().is_none());
+    assert!(db.rollback_profile("work", master, meta.clone()).is_err());
+
+    db.rollback_profile("work", revisions[2].id, meta).unwrap();
+    assert_eq!(
+        db.profile_settings(Some("work")).unwrap(),
+        settings_with("jira")
+    );
+    assert_eq!(db.list_profile_revisions("work", 10).unwrap().len(), 4);
+
+    db.remove_profile("work").unwrap();
+    assert!(db.list_profile_revisions("work", 10).unwrap().is_empty());
+}
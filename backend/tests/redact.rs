@@ -0,0 +1,138 @@
+mod common;
+
+use std::process::Command;
+
+use backend::adapter::AgentKind;
+use backend::config::{McpServer, McpSettings, McpTransport, SyncStatus, ToolConfiguration};
+use backend::db::ToolRecord;
+use backend::redact::{self, Redact};
+use backend::secrets::Secrets;
+use backend::sync;
+use common::Scratch;
+use serde_json::json;
+
+fn master() -> McpSettings {
+    let mut server = McpServer {
+        id: "github".to_string(),
+        api_key: Some("plain-api-key-123456".to_string()),
+        args: vec!["--token".to_string(), "ghp_abcdefghijklmnop".to_string()],
+        ..McpServer::default()
+    };
+    server
+        .env
+        .insert("GITHUB_TOKEN".to_string(), "${secret:gh}".to_string());
+    server.env.insert("DEBUG".to_string(), "1".to_string());
+    server.extra.insert(
+        "headers".to_string(),
+        json!({ "Authorization": "Bearer abcdefghijklmnop" }),
+    );
+    McpSettings {
+        servers: vec![server],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn masks_credentials_but_keeps_references() {
+    let redacted = master().redacted();
+    let server = &redacted.servers[0];
+    assert_eq!(server.api_key.as_deref(), Some("plai****"));
+    assert_eq!(server.args, ["--token", "ghp_****"]);
+    assert_eq!(server.env["GITHUB_TOKEN"], "${secret:gh}");
+    assert_eq!(server.env["DEBUG"], "1");
+    assert_eq!(server.extra["headers"]["Authorization"], "Bear****");
+    assert_eq!(server.endpoint, "http://localhost:3001");
+
+    assert_eq!(redact::sensitive_values(&master()).len(), 3);
+}
+
+#[test]
+fn restores_masked_values_sent_back_unchanged() {
+    let current = master();
+    let mut incoming = current.clone().redacted();
+    incoming.servers[0].endpoint = "https://changed.example".to_string();
+    redact::restore_masked(&mut incoming, &current);
+
+    let mut expected = current;
+    expected.servers[0].endpoint = "https://changed.example".to_string();
+    assert_eq!(incoming, expected);
+}
+
+#[test]
+fn plan_output_masks_values_that_are_escaped_when_rendered() {
+    let dir = Scratch::new("redact-plan");
+    let path = dir.write("cursor/mcp.json", "{\"mcpServers\": {}}");
+    let master = McpSettings {
+        servers: vec![McpServer {
+            id: "docs".to_string(),
+            transport: McpTransport::Sse,
+            endpoint: "https://docs.dev/sse".to_string(),
+            api_key: Some("sk-abcd\"efghijklmnop".to_string()),
+            ..McpServer::default()
+        }],
+        ..Default::default()
+    };
+    let tool = ToolConfiguration::new(
+        "cursor",
+        AgentKind::Cursor,
+        path.to_string_lossy(),
+        McpSettings::default(),
+    );
+
+    let plan = sync::plan_tool(&tool, &master, None, &Secrets::default()).unwrap();
+    assert!(plan.proposed.contains("efghijklmnop"));
+    let redacted = plan.redacted();
+    assert!(!redacted.proposed.contains("efghijklmnop"));
+    assert!(redacted.proposed.contains("Bearer sk-a****"));
+}
+
+#[test]
+fn failed_sync_messages_are_redacted_before_recording() {
+    let dir = Scratch::new("redact-failed");
+    let db = dir.database();
+    db.set_secret("gh", "stored-secret-value-123").unwrap();
+    let path = dir.write(
+        "codex/config.toml",
+        "[mcp_servers.gh]\nenv = { TOKEN = \"ghp_abcdefghijklmnop\", KEY = \"stored-secret-value-123\" \n",
+    );
+    let record = ToolRecord::new("codex", &path);
+    db.record_tool_detection(&record).unwrap();
+
+    let summary = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(summary.status, SyncStatus::Failed);
+    let history = db.recent_sync_history(1).unwrap();
+    for message in [&summary.message, &history[0].message] {
+        assert!(!message.contains("ghp_abcdefghijklmnop"), "{message}");
+        assert!(!message.contains("stored-secret-value-123"), "{message}");
+    }
+}
+
+#[test]
+fn cli_reveal_requires_the_admin_token() {
+    let dir = Scratch::new("redact-cli");
+    let agentctl = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_agentctl"))
+            .arg("--db")
+            .arg(dir.db_path())
+            .args(args)
+            .env("HOME", dir.join("home"))
+            .env_remove("XDG_CONFIG_HOME")
+            .env("MCP_SYNC_ADMIN_TOKEN", "admin-secret")
+            .output()
+            .unwrap()
+    };
+
+    assert!(agentctl(&["master", "show"]).status.success());
+    assert!(!agentctl(&["master", "show", "--reveal"]).status.success());
+    let wrong = agentctl(&["master", "show", "--reveal", "--admin-token", "guess"]);
+    assert!(!wrong.status.success());
+    assert!(String::from_utf8_lossy(&wrong.stderr).contains("invalid admin token"));
+    let output = agentctl(&[
+        "master",
+        "show",
+        "--reveal",
+        "--admin-token",
+        "admin-secret",
+    ]);
+    assert!(output.status.success(), "{output:?}");
+}
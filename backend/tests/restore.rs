@@ -0,0 +1,117 @@
+mod common;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+use backend::config::{McpServer, McpSettings, RevisionMeta, RevisionSource, SyncStatus};
+use backend::db::{Database, ToolRecord};
+use backend::sync;
+use common::Scratch;
+
+const ORIGINAL: &str = "{\n  \"mcpServers\": {}\n}\n";
+
+fn set_master(db: &Database, endpoint: &str) {
+    let settings = McpSettings {
+        servers: vec![McpServer {
+            id: "docs".to_string(),
+            name: "docs".to_string(),
+            endpoint: endpoint.to_string(),
+            ..McpServer::default()
+        }],
+        ..Default::default()
+    };
+    db.upsert_master_config(&settings, &RevisionMeta::new(RevisionSource::Cli, None))
+        .unwrap();
+}
+
+#[test]
+fn restores_snapshot_bytes_and_permissions() {
+    let dir = Scratch::new("restore");
+    let db = dir.database();
+    let path = dir.write("claude-desktop/claude_desktop_config.json", ORIGINAL);
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+    let record = ToolRecord::new("claude-desktop", &path);
+    db.record_tool_detection(&record).unwrap();
+
+    set_master(&db, "https://a.dev");
+    let first = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(first.status, SyncStatus::Updated);
+    let after_first = fs::read(&path).unwrap();
+    let backup = db.find_backup("claude-desktop", first.id).unwrap().unwrap();
+    assert_eq!(backup.sync_id, first.id);
+    assert_eq!(backup.content.as_deref(), Some(ORIGINAL));
+
+    set_master(&db, "https://b.dev");
+    let second = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(second.status, SyncStatus::Updated);
+    assert_ne!(fs::read(&path).unwrap(), after_first);
+
+    let restored = sync::restore_tool(&db, "claude-desktop", None).unwrap();
+    assert_eq!(
+        restored.message,
+        format!(
+            "Restored snapshot taken before sync #{}",
+            second.id.unwrap()
+        )
+    );
+    assert_eq!(fs::read(&path).unwrap(), after_first);
+    let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode, 0o600);
+
+    sync::restore_tool(&db, "claude-desktop", first.id).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), ORIGINAL);
+    assert!(sync::restore_tool(&db, "claude-desktop", Some(9999)).is_err());
+}
+
+#[test]
+fn backups_keep_resolved_secrets_encrypted() {
+    let dir = Scratch::new("sealed");
+    let db_path = dir.join("mcp_sync.db");
+    let db = Database::initialize(&db_path).unwrap();
+    db.set_secret("docs", "sk-live-abcdef123456").unwrap();
+    let path = dir.join("cursor/mcp.json");
+    let record = ToolRecord::new("cursor", &path);
+    db.record_tool_detection(&record).unwrap();
+
+    let mut settings = McpSettings {
+        servers: vec![McpServer {
+            id: "docs".to_string(),
+            endpoint: "https://a.dev".to_string(),
+            api_key: Some("${secret:docs}".to_string()),
+            ..McpServer::default()
+        }],
+        ..Default::default()
+    };
+    db.upsert_master_config(&settings, &RevisionMeta::new(RevisionSource::Cli, None))
+        .unwrap();
+    sync::sync_registered_tool(&db, &record).unwrap();
+    let synced = fs::read_to_string(&path).unwrap();
+    assert!(synced.contains("sk-live-abcdef123456"));
+
+    settings.servers[0].endpoint = "https://b.dev".to_string();
+    db.upsert_master_config(&settings, &RevisionMeta::new(RevisionSource::Cli, None))
+        .unwrap();
+    let second = sync::sync_registered_tool(&db, &record).unwrap();
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let mut stmt = conn
+        .prepare("SELECT content, ciphertext FROM config_backups")
+        .unwrap();
+    let rows: Vec<(Option<String>, Option<Vec<u8>>)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(rows.len(), 2);
+    for (content, ciphertext) in rows {
+        assert!(content.is_none());
+        if let Some(ciphertext) = ciphertext {
+            assert!(!String::from_utf8_lossy(&ciphertext).contains("sk-live"));
+        }
+    }
+
+    let backup = db.find_backup("cursor", second.id).unwrap().unwrap();
+    assert_eq!(backup.content.as_deref(), Some(synced.as_str()));
+    sync::restore_tool(&db, "cursor", second.id).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), synced);
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use backend::config::{McpServer, McpSettings, RevisionMeta, RevisionSource};
+use common::Scratch;
+
+fn settings(endpoint: &str) -> McpSettings {
+    McpSettings {
+        servers: vec![McpServer {
+            id: "docs".to_string(),
+            name: "docs".to_string(),
+            endpoint: endpoint.to_string(),
+            ..McpServer::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn rollback_appends_a_revision_and_keeps_history() {
+    let dir = Scratch::new("revisions");
+    let db = dir.database();
+
+    let meta = RevisionMeta::new(RevisionSource::Cli, Some("alice".to_string()));
+    let first = db
+        .upsert_master_config(&settings("https://a.dev"), &meta.clone().with_message("a"))
+        .unwrap();
+    let second = db
+        .upsert_master_config(&settings("https://b.dev"), &meta)
+        .unwrap();
+
+    // 최신 리비전이 먼저 나오고 limit 을 따릅니다.
+    let revisions = db.list_master_revisions(10).unwrap();
+    assert_eq!(
+        revisions.iter().map(|r| r.id).collect::<Vec<_>>(),
+        [second, first]
+    );
+    assert_eq!(db.list_master_revisions(1).unwrap().len(), 1);
+    let stored = db.get_master_revision(first).unwrap().unwrap();
+    assert_eq!(stored.settings.servers[0].endpoint, "https://a.dev");
+    assert_eq!(stored.author.as_deref(), Some("alice"));
+    assert_eq!(stored.message.as_deref(), Some("a"));
+    assert!(db.get_master_revision(second + 100).unwrap().is_none());
+
+    let restored = db
+        .rollback_master_config(first, RevisionMeta::new(RevisionSource::Api, None))
+        .unwrap();
+    assert_eq!(restored.settings.servers[0].endpoint, "https://a.dev");
+    assert_eq!(
+        db.get_master_config().unwrap().settings.servers[0].endpoint,
+        "https://a.dev"
+    );
+
+    // 롤백은 기존 리비전을 지우지 않고 새 리비전을 추가합니다.
+    let revisions = db.list_master_revisions(10).unwrap();
+    assert_eq!(revisions.len(), 3);
+    assert_eq!(
+        &revisions[1..].iter().map(|r| r.id).collect::<Vec<_>>(),
+        &[second, first]
+    );
+    assert_eq!(revisions[0].source, RevisionSource::Api);
+    assert_eq!(
+        revisions[0].message.as_deref(),
+        Some(format!("rollback to revision #{first}").as_str())
+    );
+
+    assert!(db
+        .rollback_master_config(second + 100, RevisionMeta::new(RevisionSource::Api, None))
+        .is_err());
+}
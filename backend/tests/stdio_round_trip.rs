@@ -0,0 +1,180 @@
+mod common;
+
+use std::fs;
+use std::process::Command;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use backend::adapter::AgentKind;
+use backend::api::{router, AppState};
+use backend::config::{
+    McpSettings, McpTransport, RecommendedServer, RevisionMeta, RevisionSource, SyncPolicy,
+};
+use backend::settings::{Settings, SettingsOverrides};
+use backend::sync::merge_settings;
+use common::Scratch;
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+// `transport` 없이 `command` 만 가진 서버
+const MASTER: &str = r#"{
+  "servers": [
+    {
+      "id": "files",
+      "name": "files",
+      "description": null,
+      "command": "npx",
+      "args": ["-y", "@modelcontextprotocol/server-filesystem"],
+      "env": {"ROOT": "/srv/files"},
+      "cwd": "/srv",
+      "api_key": null,
+      "enabled": true
+    }
+  ]
+}"#;
+
+fn master() -> McpSettings {
+    serde_json::from_str(MASTER).unwrap()
+}
+
+#[test]
+fn command_without_transport_is_stdio() {
+    let server = &master().servers[0];
+    assert_eq!(server.transport, McpTransport::Stdio);
+    assert_eq!(server.command.as_deref(), Some("npx"));
+
+    let serialized = serde_json::to_value(master()).unwrap();
+    assert_eq!(serialized["servers"][0]["transport"], json!("stdio"));
+    assert_eq!(
+        serde_json::from_value::<McpSettings>(serialized).unwrap(),
+        master()
+    );
+
+    for entry in [
+        json!({"id": "x", "name": "x", "description": null, "api_key": null}),
+        json!({"id": "x", "name": "x", "description": null, "api_key": null, "transport": "stdio"}),
+    ] {
+        let settings = json!({ "servers": [entry] });
+        let err = serde_json::from_value::<McpSettings>(settings).unwrap_err();
+        assert!(err.to_string().contains("server 'x'"), "{err}");
+    }
+}
+
+#[test]
+fn recommended_import_keeps_an_existing_stdio_server() {
+    let recommended = |id: &str| RecommendedServer {
+        id: id.to_string(),
+        name: "Files".to_string(),
+        description: Some("hosted files".to_string()),
+        endpoint: "https://files.dev/mcp".to_string(),
+        homepage: None,
+        category: None,
+        api_key_required: false,
+        default_enabled: true,
+    };
+
+    let mut settings = master();
+    let err = settings
+        .apply_recommended_server(&recommended("files"), false)
+        .unwrap_err();
+    assert!(err.to_string().contains("stdio"), "{err}");
+    assert_eq!(settings, master());
+
+    // 같은 전송 방식이면 비어 있는 필드만 채웁니다.
+    settings
+        .apply_recommended_server(&recommended("docs"), true)
+        .unwrap();
+    settings.servers[1].endpoint = "https://mirror.dev/mcp".to_string();
+    settings.servers[1].description = None;
+    settings
+        .apply_recommended_server(&recommended("docs"), false)
+        .unwrap();
+    let docs = &settings.servers[1];
+    assert_eq!(docs.endpoint, "https://mirror.dev/mcp");
+    assert_eq!(docs.description.as_deref(), Some("hosted files"));
+    assert!(!docs.enabled);
+}
+
+#[test]
+fn stdio_server_round_trips_through_db_cli_and_merge() {
+    let dir = Scratch::new("stdio-db");
+    let db = dir.database();
+    db.upsert_master_config(&master(), &RevisionMeta::new(RevisionSource::Cli, None))
+        .unwrap();
+    assert_eq!(db.ensure_master_config().unwrap().settings, master());
+
+    let path = dir.join("master.json");
+    fs::write(&path, MASTER).unwrap();
+    let agentctl = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_agentctl"))
+            .arg("--db")
+            .arg(dir.join("cli.db"))
+            .args(args)
+            .env("HOME", dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+    agentctl(&["master", "set", path.to_str().unwrap()]);
+    let shown: McpSettings = serde_json::from_str(&agentctl(&["master", "show"])).unwrap();
+    assert_eq!(shown, master());
+
+    let merged = merge_settings(&master(), &McpSettings::default(), SyncPolicy::Additive);
+    let adapter = AgentKind::GeminiCli.adapter();
+    let rendered = adapter.render(&merged, None).unwrap();
+    let server = &adapter.parse(&rendered).unwrap().servers[0];
+    assert_eq!(server.transport, McpTransport::Stdio);
+    assert_eq!(
+        (&server.command, &server.args, &server.env, &server.cwd),
+        (
+            &master().servers[0].command,
+            &master().servers[0].args,
+            &master().servers[0].env,
+            &master().servers[0].cwd
+        )
+    );
+}
+
+#[tokio::test]
+async fn stdio_server_round_trips_through_api() {
+    let dir = Scratch::new("stdio-api");
+    let db = dir.database();
+    let settings = Settings::load(SettingsOverrides {
+        db_path: Some(dir.db_path()),
+        config_root: None,
+    })
+    .unwrap();
+    let app = router(AppState {
+        db: Arc::new(db),
+        settings: Arc::new(settings),
+    });
+
+    let body = json!({ "settings": serde_json::from_str::<Value>(MASTER).unwrap() });
+    let response = app
+        .clone()
+        .oneshot(
+            Request::post("/api/config/master")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .oneshot(
+            Request::get("/api/config/master")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let config: Value = serde_json::from_slice(&bytes).unwrap();
+    let settings: McpSettings = serde_json::from_value(config["settings"].clone()).unwrap();
+    assert_eq!(settings, master());
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use backend::config::{
+    ConflictSide, EnvMode, McpServer, McpSettings, RevisionMeta, RevisionSource, SyncPolicy,
+    SyncStatus,
+};
+use backend::db::{Database, ToolRecord};
+use backend::sync;
+use common::Scratch;
+
+fn master(endpoint: &str) -> McpSettings {
+    McpSettings {
+        servers: vec![McpServer {
+            id: "github".to_string(),
+            endpoint: endpoint.to_string(),
+            ..McpServer::default()
+        }],
+        ..Default::default()
+    }
+}
+
+fn set_master(db: &Database, endpoint: &str) {
+    db.upsert_master_config(
+        &master(endpoint),
+        &RevisionMeta::new(RevisionSource::Cli, None),
+    )
+    .unwrap();
+}
+
+fn edit(path: &PathBuf, from: &str, to: &str) {
+    let content = fs::read_to_string(path).unwrap();
+    fs::write(path, content.replace(from, to)).unwrap();
+}
+
+#[test]
+fn keeps_local_edits_and_reports_conflicts() {
+    let dir = Scratch::new("conflict");
+    let db = dir.database();
+    let path = dir.write("cursor/mcp.json", "{\"mcpServers\": {}}");
+    let record = ToolRecord::new("cursor", &path);
+    db.record_tool_detection(&record).unwrap();
+
+    set_master(&db, "https://a.dev");
+    let summary = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(summary.status, SyncStatus::Updated);
+
+    // 마스터가 그대로면 도구에서 바꾼 값은 되돌리지 않습니다.
+    edit(&path, "https://a.dev", "https://local.dev");
+    let summary = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(summary.status, SyncStatus::Skipped);
+
+    set_master(&db, "https://b.dev");
+    let summary = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(summary.status, SyncStatus::Conflict);
+    assert!(summary.message.contains("github.endpoint"));
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("https://local.dev"));
+
+    let summary = sync::resolve_conflict(&db, &record, "github", ConflictSide::Master).unwrap();
+    assert_eq!(summary.status, SyncStatus::Updated);
+    assert!(fs::read_to_string(&path).unwrap().contains("https://b.dev"));
+    assert!(sync::resolve_conflict(&db, &record, "github", ConflictSide::Tool).is_err());
+}
+
+#[test]
+fn mirror_overwrites_local_edits_without_conflicts() {
+    let dir = Scratch::new("conflict-mirror");
+    let db = dir.database();
+    let path = dir.write("cursor/mcp.json", "{\"mcpServers\": {}}");
+    db.record_tool_detection(&ToolRecord::new("cursor", &path))
+        .unwrap();
+    db.set_tool_policy("cursor", SyncPolicy::Mirror).unwrap();
+    let record = db.find_tool("cursor").unwrap().unwrap();
+
+    set_master(&db, "https://a.dev");
+    sync::sync_registered_tool(&db, &record).unwrap();
+    assert!(db.find_sync_base("cursor").unwrap().is_some());
+
+    edit(&path, "https://a.dev", "https://local.dev");
+    let summary = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(summary.status, SyncStatus::Updated);
+    assert!(fs::read_to_string(&path).unwrap().contains("https://a.dev"));
+
+    edit(&path, "https://a.dev", "https://local.dev");
+    set_master(&db, "https://b.dev");
+    let summary = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(summary.status, SyncStatus::Updated);
+    assert!(fs::read_to_string(&path).unwrap().contains("https://b.dev"));
+}
+
+#[test]
+fn expand_keeps_local_edits_of_expanded_values() {
+    let dir = Scratch::new("conflict-expand");
+    let db = dir.database();
+    let path = dir.write("cursor/mcp.json", "{\"mcpServers\": {}}");
+    let record = ToolRecord::new("cursor", &path);
+    db.record_tool_detection(&record).unwrap();
+    std::env::set_var("MCP_SYNC_TEST_EXPAND_HOST", "a.dev");
+
+    set_master(&db, "https://${env:MCP_SYNC_TEST_EXPAND_HOST}/mcp");
+    sync::sync_registered_tool(&db, &record).unwrap();
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("https://a.dev/mcp"));
+
+    // 환경 변수 값만 바뀌었으면 로컬 수정이 아니므로 새 값으로 다시 풉니다.
+    std::env::set_var("MCP_SYNC_TEST_EXPAND_HOST", "b.dev");
+    let summary = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(summary.status, SyncStatus::Updated);
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("https://b.dev/mcp"));
+
+    edit(&path, "https://b.dev/mcp", "https://local.dev/mcp");
+    let summary = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(summary.status, SyncStatus::Skipped);
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("https://local.dev/mcp"));
+
+    set_master(&db, "https://${env:MCP_SYNC_TEST_EXPAND_HOST}/v2");
+    let summary = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(summary.status, SyncStatus::Conflict);
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("https://local.dev/mcp"));
+}
+
+#[test]
+fn passthrough_keeps_local_edits_of_env_references() {
+    let dir = Scratch::new("conflict-passthrough");
+    let db = dir.database();
+    let path = dir.write("cursor/mcp.json", "{\"mcpServers\": {}}");
+    db.record_tool_detection(&ToolRecord::new("cursor", &path))
+        .unwrap();
+    db.set_tool_env_mode("cursor", EnvMode::Passthrough)
+        .unwrap();
+    let record = db.find_tool("cursor").unwrap().unwrap();
+
+    set_master(&db, "https://${env:MCP_SYNC_TEST_PASSTHROUGH_HOST}/mcp");
+    sync::sync_registered_tool(&db, &record).unwrap();
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("${env:MCP_SYNC_TEST_PASSTHROUGH_HOST}"));
+
+    edit(&path, "${env:MCP_SYNC_TEST_PASSTHROUGH_HOST}", "local.dev");
+    let summary = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(summary.status, SyncStatus::Skipped);
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("https://local.dev/mcp"));
+
+    set_master(&db, "https://${env:MCP_SYNC_TEST_PASSTHROUGH_HOST}/v2");
+    let summary = sync::sync_registered_tool(&db, &record).unwrap();
+    assert_eq!(summary.status, SyncStatus::Conflict);
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("https://local.dev/mcp"));
+}
@@ -0,0 +1,69 @@
+use backend::config::{McpServer, McpSettings, SyncPolicy};
+use backend::sync::merge_settings;
+
+fn server(id: &str, endpoint: &str) -> McpServer {
+    McpServer {
+        id: id.to_string(),
+        endpoint: endpoint.to_string(),
+        ..McpServer::default()
+    }
+}
+
+fn ids(settings: &McpSettings) -> Vec<(&str, &str)> {
+    settings
+        .servers
+        .iter()
+        .map(|server| (server.id.as_str(), server.endpoint.as_str()))
+        .collect()
+}
+
+#[test]
+fn merge_follows_tool_policy() {
+    let master = McpSettings {
+        servers: vec![
+            server("shared", "https://master.example"),
+            server("master-only", "https://m.example"),
+        ],
+        ..Default::default()
+    };
+    let tool = McpSettings {
+        servers: vec![
+            server("shared", "https://local.example"),
+            server("local-only", "https://l.example"),
+        ],
+        ..Default::default()
+    };
+
+    assert_eq!(
+        ids(&merge_settings(&master, &tool, SyncPolicy::Mirror)),
+        [
+            ("shared", "https://master.example"),
+            ("master-only", "https://m.example")
+        ]
+    );
+    assert_eq!(
+        ids(&merge_settings(&master, &tool, SyncPolicy::Additive)),
+        [
+            ("shared", "https://master.example"),
+            ("master-only", "https://m.example"),
+            ("local-only", "https://l.example")
+        ]
+    );
+    assert_eq!(
+        ids(&merge_settings(&master, &tool, SyncPolicy::PreserveLocal)),
+        [
+            ("shared", "https://local.example"),
+            ("local-only", "https://l.example"),
+            ("master-only", "https://m.example")
+        ]
+    );
+}
+
+#[test]
+fn policy_parses_cli_spelling() {
+    assert_eq!(
+        "preserve-local".parse::<SyncPolicy>().unwrap(),
+        SyncPolicy::PreserveLocal
+    );
+    assert!("overwrite".parse::<SyncPolicy>().is_err());
+}
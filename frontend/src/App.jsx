@@ -3,6 +3,7 @@ import {
   fetchMasterConfig,
   fetchRecommendedServers,
   fetchSyncHistory,
+  fetchToolDiff,
   fetchTools,
   importRecommendedServer,
   rescanTools,
@@ -16,6 +17,7 @@ import RecommendedServerList from './components/RecommendedServerList.jsx';
 
 const App = () => {
   const [tools, setTools] = useState([]);
+  const [diffByTool, setDiffByTool] = useState({});
   const [masterConfig, setMasterConfig] = useState(null);
   const [masterConfigDraft, setMasterConfigDraft] = useState('');
   const [history, setHistory] = useState([]);
@@ -57,6 +59,24 @@ const App = () => {
     bootstrap();
   }, []);
 
+  useEffect(() => {
+    loadDiffs(tools);
+  }, [tools, masterConfig]);
+
+  const loadDiffs = async (toolList) => {
+    try {
+      const diffs = await Promise.all(toolList.map((tool) => fetchToolDiff(tool.name)));
+      setDiffByTool(
+        diffs.reduce((acc, diff) => {
+          acc[diff.tool] = diff.in_sync ? null : diff.changes;
+          return acc;
+        }, {})
+      );
+    } catch (err) {
+      console.error('Failed to load diffs', err);
+    }
+  };
+
   const bootstrap = async () => {
     await runTask(
       () =>
@@ -107,7 +127,14 @@ const App = () => {
           );
         }
       },
-      successMessage: '동기화가 완료되었습니다.',
+      successMessage: (results) => {
+        const failed = results.filter((item) => item.status === 'failed');
+        return failed.length
+          ? `동기화를 마쳤지만 ${failed.length}개 도구가 실패했습니다: ${failed
+              .map((item) => item.tool)
+              .join(', ')}`
+          : '동기화가 완료되었습니다.';
+      },
       errorMessage: '동기화 중 오류가 발생했습니다.'
     });
     return summaries;
@@ -144,14 +171,6 @@ const App = () => {
     );
   };
 
-  const diffByTool = useMemo(() => {
-    if (!masterConfig) return {};
-    return tools.reduce((acc, tool) => {
-      acc[tool.name] = computeConfigDiff(masterConfig.settings, tool.settings);
-      return acc;
-    }, {});
-  }, [masterConfig, tools]);
-
   const masterServerIds = useMemo(() => {
     if (!masterConfig) return new Set();
     return new Set(masterConfig.settings.servers.map((server) => server.id));
@@ -216,21 +235,4 @@ const App = () => {
   );
 };
 
-function computeConfigDiff(master, tool) {
-  try {
-    const masterServers = JSON.stringify(master.servers);
-    const toolServers = JSON.stringify(tool.servers);
-    if (masterServers === toolServers) {
-      return null;
-    }
-    return {
-      master: master.servers,
-      tool: tool.servers
-    };
-  } catch (err) {
-    console.error('Failed to compute diff', err);
-    return null;
-  }
-}
-
 export default App;
@@ -29,6 +29,9 @@ function translateStatus(status) {
     case 'Skipped':
     case 'skipped':
       return '유지';
+    case 'Conflict':
+    case 'conflict':
+      return '충돌';
     default:
       return '실패';
   }
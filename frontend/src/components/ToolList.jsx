@@ -24,7 +24,7 @@ const ToolList = ({ tools, onSync, diffs, loading }) => {
               {diffs[tool.name] ? (
                 <details>
                   <summary>차이 보기</summary>
-                  <pre>{JSON.stringify(diffs[tool.name], null, 2)}</pre>
+                  <DiffView changes={diffs[tool.name]} />
                 </details>
               ) : (
                 <span className="pill pill-success">동일</span>
@@ -42,4 +42,37 @@ const ToolList = ({ tools, onSync, diffs, loading }) => {
   );
 };
 
+const CHANGE_LABELS = {
+  added: '+',
+  removed: '-',
+  modified: '~'
+};
+
+const DiffView = ({ changes }) => (
+  <ul className="diff-list">
+    {changes.servers.map((change) => (
+      <li key={`server-${change.id}`} className={`diff-${change.change}`}>
+        {CHANGE_LABELS[change.change]} {change.id}
+        {change.fields && <FieldList fields={change.fields} />}
+      </li>
+    ))}
+    {changes.project_overrides.map((change) => (
+      <li key={`project-${change.project}`} className={`diff-${change.change}`}>
+        {CHANGE_LABELS[change.change]} 프로젝트 {change.project}
+        {change.fields && <FieldList fields={change.fields} />}
+      </li>
+    ))}
+  </ul>
+);
+
+const FieldList = ({ fields }) => (
+  <ul>
+    {fields.map((field) => (
+      <li key={field.field}>
+        <code>{field.field}</code>: {JSON.stringify(field.tool)} → {JSON.stringify(field.master)}
+      </li>
+    ))}
+  </ul>
+);
+
 export default ToolList;
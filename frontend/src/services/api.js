@@ -22,11 +22,39 @@ export async function rescanTools() {
   return handleResponse(response);
 }
 
+export async function fetchToolDiff(name) {
+  const response = await fetch(`/api/tools/${encodeURIComponent(name)}/diff`);
+  return handleResponse(response);
+}
+
+export async function restoreTool(name, syncId) {
+  const response = await fetch(`/api/tools/${encodeURIComponent(name)}/restore`, {
+    method: 'POST',
+    headers: jsonHeaders,
+    body: JSON.stringify({ sync_id: syncId ?? null })
+  });
+  return handleResponse(response);
+}
+
 export async function fetchMasterConfig() {
   const response = await fetch('/api/config/master');
   return handleResponse(response);
 }
 
+export async function fetchMasterRevisions() {
+  const response = await fetch('/api/config/master/revisions');
+  return handleResponse(response);
+}
+
+export async function rollbackMasterConfig(revision, author) {
+  const response = await fetch(`/api/config/master/revisions/${revision}/rollback`, {
+    method: 'POST',
+    headers: jsonHeaders,
+    body: JSON.stringify({ author: author ?? null })
+  });
+  return handleResponse(response);
+}
+
 export async function fetchRecommendedServers() {
   const response = await fetch('/api/config/recommended');
   return handleResponse(response);
@@ -50,11 +78,11 @@ export async function importRecommendedServer(serverId, enabled) {
   return handleResponse(response);
 }
 
-export async function syncTools(tool) {
+export async function syncTools(tool, { dryRun = false } = {}) {
   const response = await fetch('/api/sync', {
     method: 'POST',
     headers: jsonHeaders,
-    body: JSON.stringify({ tool: tool ?? null })
+    body: JSON.stringify({ tool: tool ?? null, dry_run: dryRun })
   });
   return handleResponse(response);
 }